@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Context, Result};
 use tokio::time::Duration;
-use wasmcloud_control_interface::Client as CtlClient;
+use wasmcloud_control_interface::{Client as CtlClient, CtlOperationAck};
 
 use crate::{
     common::boxed_err_to_anyhow,
@@ -117,6 +117,22 @@ pub async fn scale_actor(
     Ok(())
 }
 
+/// Update a running actor in place to the actor at `new_actor_ref`. The host rejects the update, rather than
+/// failing, when it can't be applied, e.g. when the new actor is signed with a different key
+pub async fn update_actor(
+    client: &CtlClient,
+    host_id: &str,
+    actor_id: &str,
+    new_actor_ref: &str,
+    annotations: Option<HashMap<String, String>>,
+) -> Result<CtlOperationAck> {
+    client
+        .update_actor(host_id, actor_id, new_actor_ref, annotations)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("Failed to update actor {} to {}", actor_id, new_actor_ref))
+}
+
 /// Stop an actor
 pub async fn stop_actor(
     client: &CtlClient,
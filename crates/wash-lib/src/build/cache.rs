@@ -0,0 +1,347 @@
+//! A content-addressed cache of built artifacts, allowing builds with unchanged inputs to skip
+//! compilation and signing entirely
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, Result};
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+
+use super::{
+    is_key_path, provenance::git_state, workspace::local_dependency_paths, BuildArtifact,
+    SignConfig,
+};
+use crate::parser::{LanguageConfig, ProjectConfig, TypeConfig};
+
+/// Name of the file, inside of a cache entry, describing the artifact and the path it was originally built to
+const ARTIFACT_FILE: &str = "artifact.json";
+
+/// Top level directories of a project that contain build outputs and should not be considered source inputs
+pub(crate) const IGNORED_PROJECT_DIRS: [&str; 3] = ["target", "build", ".git"];
+
+/// Lock files pinning the versions of the dependencies of a project, which may be shared with a workspace
+const LOCK_FILES: [&str; 2] = ["Cargo.lock", "go.sum"];
+
+/// Computes the cache key for building a project. The key is a hash of the project's source files, the
+/// sources of its local path dependencies, the nearest lock file, the resolved [ProjectConfig], the version of the language toolchain, the signing inputs, and the git
+/// state recorded in the artifact's provenance.
+///
+/// The WASI adapter and the seed files pinned in the [ActorConfig](crate::parser::ActorConfig) are hashed by
+/// content. Keys that are sourced from a keys directory (rather than supplied directly) are identified by the
+/// directory they are found in, not by their contents.
+pub(crate) fn cache_key(config: &ProjectConfig, signing: Option<&SignConfig>) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    hasher.update(format!("{config:?}").as_bytes());
+    hasher.update(toolchain_version(config)?.as_bytes());
+    // Signed actors embed the git commit they were built from, so it must match for a cached artifact to be used
+    hasher.update(format!("{:?}", git_state(&config.common.path)).as_bytes());
+
+    match signing {
+        Some(sign_config) => {
+            hasher.update(format!("{sign_config:?}").as_bytes());
+            // Keys supplied as paths are hashed by content, so replacing a key invalidates the cache
+            for key in [&sign_config.issuer, &sign_config.subject]
+                .into_iter()
+                .flatten()
+            {
+                if let Ok(contents) = fs::read(key) {
+                    hasher.update(contents);
+                }
+            }
+        }
+        None => hasher.update(b"unsigned"),
+    }
+
+    // Files referenced by the actor config are hashed by content too, as they may live outside of the project
+    if let TypeConfig::Actor(actor_config) = &config.project_type {
+        let key_paths = [&actor_config.issuer_key, &actor_config.subject_key]
+            .into_iter()
+            .flatten()
+            .filter(|key| is_key_path(key))
+            .map(PathBuf::from);
+        for path in actor_config
+            .wasi_adapter_path
+            .iter()
+            .cloned()
+            .chain(key_paths)
+        {
+            if let Ok(contents) = fs::read(config.common.path.join(path)) {
+                hasher.update(contents);
+            }
+        }
+    }
+
+    hash_inputs(&mut hasher, &config.common.path)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Looks up a previously built artifact for the given cache key. If one is found, it is restored to the
+/// path it was originally built to and returned.
+pub(crate) fn lookup(cache_dir: &Path, key: &str) -> Result<Option<BuildArtifact>> {
+    let entry_dir = cache_dir.join(key);
+    let artifact: BuildArtifact = match fs::read(entry_dir.join(ARTIFACT_FILE)) {
+        Ok(artifact) => serde_json::from_slice(&artifact)?,
+        Err(_) => return Ok(None),
+    };
+    let artifact_path = &artifact.path;
+    let cached_artifact = entry_dir.join(artifact_file_name(artifact_path)?);
+    if !cached_artifact.is_file() {
+        return Ok(None);
+    }
+
+    // Restore the artifact if it was removed or replaced since it was cached
+    let cached_contents = fs::read(&cached_artifact)?;
+    if fs::read(artifact_path).ok().as_ref() != Some(&cached_contents) {
+        if let Some(p) = artifact_path.parent() {
+            fs::create_dir_all(p)?;
+        }
+        fs::write(artifact_path, cached_contents)?;
+    }
+
+    Ok(Some(artifact))
+}
+
+/// Stores a built artifact in the cache under the given cache key
+pub(crate) fn store(cache_dir: &Path, key: &str, artifact: &BuildArtifact) -> Result<()> {
+    let entry_dir = cache_dir.join(key);
+    fs::create_dir_all(&entry_dir)?;
+    fs::copy(
+        &artifact.path,
+        entry_dir.join(artifact_file_name(&artifact.path)?),
+    )?;
+    fs::write(entry_dir.join(ARTIFACT_FILE), serde_json::to_vec(artifact)?)?;
+    Ok(())
+}
+
+fn artifact_file_name(artifact_path: &Path) -> Result<&std::ffi::OsStr> {
+    artifact_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid artifact path {}", artifact_path.display()))
+}
+
+/// Returns the version string reported by the language toolchain used to build the project
+fn toolchain_version(config: &ProjectConfig) -> Result<String> {
+    let mut command = match &config.language {
+        LanguageConfig::Rust(rust_config) => {
+            let mut command = Command::new(
+                rust_config
+                    .cargo_path
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from("cargo")),
+            );
+            command.arg("--version");
+            command
+        }
+        LanguageConfig::TinyGo(tinygo_config) => {
+            let mut command = Command::new(
+                tinygo_config
+                    .tinygo_path
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from("tinygo")),
+            );
+            command.arg("version");
+            command
+        }
+        // The toolchain of a custom build command is unknown, so only the command itself is considered
+        LanguageConfig::Custom(_) => return Ok(String::new()),
+    };
+
+    // The toolchain may be selected per directory (e.g. with a rust-toolchain.toml file)
+    let output = command
+        .current_dir(&config.common.path)
+        .output()
+        .map_err(|e| anyhow!("Failed to determine toolchain version: {e}"))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Hashes the sources of a project, the sources of the local path dependencies it is built with (following their
+/// own path dependencies), and the nearest `Cargo.lock` or `go.sum` file
+fn hash_inputs(hasher: &mut Sha256, project_path: &Path) -> Result<()> {
+    hash_source_files(hasher, project_path)?;
+
+    let project_dir = fs::canonicalize(project_path)?;
+    let mut visited = HashSet::from([project_dir.clone()]);
+    let mut pending = local_dependency_paths(&project_dir);
+    while let Some(dependency) = pending.pop() {
+        if !visited.insert(dependency.clone()) {
+            continue;
+        }
+        pending.extend(local_dependency_paths(&dependency));
+        // Dependencies inside of the project are already hashed as part of its sources
+        if dependency.starts_with(&project_dir) {
+            continue;
+        }
+        hasher.update(dependency.to_string_lossy().as_bytes());
+        hash_source_files(hasher, &dependency)?;
+    }
+
+    let lock_file = project_dir
+        .ancestors()
+        .flat_map(|dir| LOCK_FILES.iter().map(move |file| dir.join(file)))
+        .find(|path| path.is_file());
+    if let Some(lock_file) = lock_file {
+        hasher.update(lock_file.to_string_lossy().as_bytes());
+        hasher.update(fs::read(&lock_file)?);
+    }
+
+    Ok(())
+}
+
+/// Hashes the relative path and contents of every source file in the project directory, respecting
+/// ignore files and skipping build output directories
+fn hash_source_files(hasher: &mut Sha256, project_path: &Path) -> Result<()> {
+    let walker = WalkBuilder::new(project_path)
+        .hidden(false)
+        .filter_entry(|entry| {
+            entry.depth() != 1
+                || !IGNORED_PROJECT_DIRS
+                    .iter()
+                    .any(|dir| entry.file_name() == *dir)
+        })
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let relative_path = entry.path().strip_prefix(project_path)?;
+        hasher.update(relative_path.to_string_lossy().as_bytes());
+        hasher.update(fs::read(entry.path())?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn source_hash_changes_with_sources_only() {
+        let project = tempfile::tempdir().expect("Unable to create tempdir");
+        fs::create_dir_all(project.path().join("src")).unwrap();
+        fs::create_dir_all(project.path().join("build")).unwrap();
+        fs::write(project.path().join("src/lib.rs"), "fn main() {}").unwrap();
+
+        let hash = |path: &Path| {
+            let mut hasher = Sha256::new();
+            hash_source_files(&mut hasher, path).unwrap();
+            format!("{:x}", hasher.finalize())
+        };
+
+        let original = hash(project.path());
+
+        // Build outputs should not affect the hash
+        fs::write(project.path().join("build/actor_s.wasm"), "signed").unwrap();
+        assert_eq!(original, hash(project.path()));
+
+        fs::write(project.path().join("src/lib.rs"), "fn main() { }").unwrap();
+        assert_ne!(original, hash(project.path()));
+    }
+
+    #[test]
+    fn source_hash_changes_with_path_dependencies_and_lock_file() {
+        let workspace = tempfile::tempdir().expect("Unable to create tempdir");
+        let project = workspace.path().join("actor");
+        let shared = workspace.path().join("shared");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(shared.join("src")).unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[dependencies]\nshared = { path = \"../shared\" }\n",
+        )
+        .unwrap();
+        fs::write(project.join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(shared.join("src/lib.rs"), "pub fn shared() {}").unwrap();
+        fs::write(workspace.path().join("Cargo.lock"), "version = 3").unwrap();
+
+        let hash = |path: &Path| {
+            let mut hasher = Sha256::new();
+            hash_inputs(&mut hasher, path).unwrap();
+            format!("{:x}", hasher.finalize())
+        };
+
+        let original = hash(&project);
+        assert_eq!(original, hash(&project));
+
+        fs::write(shared.join("src/lib.rs"), "pub fn shared() { }").unwrap();
+        let edited = hash(&project);
+        assert_ne!(original, edited);
+
+        fs::write(workspace.path().join("Cargo.lock"), "version = 4").unwrap();
+        assert_ne!(edited, hash(&project));
+    }
+
+    #[test]
+    fn cache_key_changes_with_files_referenced_by_the_actor_config() {
+        let workspace = tempfile::tempdir().expect("Unable to create tempdir");
+        let project = workspace.path().join("actor");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(workspace.path().join("keys")).unwrap();
+        fs::write(project.join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(workspace.path().join("adapter.wasm"), "adapter").unwrap();
+        fs::write(workspace.path().join("keys/account.nk"), "SAAOLD").unwrap();
+
+        let config = ProjectConfig {
+            language: LanguageConfig::Custom(Default::default()),
+            project_type: TypeConfig::Actor(crate::parser::ActorConfig {
+                wasi_adapter_path: Some(PathBuf::from("../adapter.wasm")),
+                issuer_key: Some("../keys/account.nk".to_string()),
+                ..Default::default()
+            }),
+            common: crate::parser::CommonConfig {
+                name: "actor".to_string(),
+                version: semver::Version::new(0, 1, 0),
+                path: project,
+                wasm_bin_name: None,
+                hooks: Default::default(),
+                dev: Default::default(),
+            },
+        };
+
+        let original = cache_key(&config, None).unwrap();
+        assert_eq!(original, cache_key(&config, None).unwrap());
+
+        fs::write(workspace.path().join("adapter.wasm"), "new adapter").unwrap();
+        let adapted = cache_key(&config, None).unwrap();
+        assert_ne!(original, adapted);
+
+        fs::write(workspace.path().join("keys/account.nk"), "SAANEW").unwrap();
+        assert_ne!(adapted, cache_key(&config, None).unwrap());
+    }
+
+    #[test]
+    fn can_store_and_restore_artifacts() {
+        let cache_dir = tempfile::tempdir().expect("Unable to create tempdir");
+        let project = tempfile::tempdir().expect("Unable to create tempdir");
+        let artifact = project.path().join("build/actor_s.wasm");
+        fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        fs::write(&artifact, "signed").unwrap();
+
+        let built = BuildArtifact {
+            path: artifact.clone(),
+            digest: "sha256:0a1b".to_string(),
+            size: 6,
+            signing_keys: None,
+            duration: std::time::Duration::from_secs(3),
+            size_report: None,
+        };
+
+        assert!(lookup(cache_dir.path(), "abc").unwrap().is_none());
+        store(cache_dir.path(), "abc", &built).unwrap();
+
+        fs::remove_file(&artifact).unwrap();
+        assert_eq!(lookup(cache_dir.path(), "abc").unwrap(), Some(built));
+        assert_eq!(fs::read_to_string(&artifact).unwrap(), "signed");
+    }
+}
@@ -0,0 +1,134 @@
+//! Run the user-defined commands configured to hook into the stages of a build
+
+use std::{fmt, path::Path};
+
+use tokio::process::Command;
+
+use anyhow::{anyhow, bail, Result};
+
+use super::run_command;
+use crate::parser::CommonConfig;
+
+/// Environment variable containing the path to the artifact produced by the build stage a hook runs after
+pub const ARTIFACT_PATH_ENV: &str = "WASH_ARTIFACT_PATH";
+/// Environment variable containing the name of the project being built
+pub const PROJECT_NAME_ENV: &str = "WASH_PROJECT_NAME";
+/// Environment variable containing the version of the project being built
+pub const PROJECT_VERSION_ENV: &str = "WASH_PROJECT_VERSION";
+
+/// The stage of a build that a hook runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookStage {
+    PreBuild,
+    PostBuild,
+    PostSign,
+}
+
+impl fmt::Display for HookStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookStage::PreBuild => write!(f, "pre_build"),
+            HookStage::PostBuild => write!(f, "post_build"),
+            HookStage::PostSign => write!(f, "post_sign"),
+        }
+    }
+}
+
+/// Runs the hooks configured for `stage` in order, stopping at the first hook that fails.
+///
+/// Hooks are run in the project directory with [PROJECT_NAME_ENV] and [PROJECT_VERSION_ENV] set, as well as
+/// [ARTIFACT_PATH_ENV] when an artifact has been produced.
+pub(crate) async fn run_hooks(
+    stage: HookStage,
+    common_config: &CommonConfig,
+    artifact_path: Option<&Path>,
+) -> Result<()> {
+    let hooks = match stage {
+        HookStage::PreBuild => &common_config.hooks.pre_build,
+        HookStage::PostBuild => &common_config.hooks.post_build,
+        HookStage::PostSign => &common_config.hooks.post_sign,
+    };
+
+    for hook in hooks {
+        let mut command = shell_command(hook);
+        command
+            .current_dir(&common_config.path)
+            .env(PROJECT_NAME_ENV, &common_config.name)
+            .env(PROJECT_VERSION_ENV, common_config.version.to_string());
+        if let Some(path) = artifact_path {
+            command.env(ARTIFACT_PATH_ENV, path);
+        }
+
+        let result = run_command(&mut command)
+            .await
+            .map_err(|e| anyhow!("Failed to run {stage} hook `{hook}`: {e}"))?;
+        if !result.success() {
+            bail!("{stage} hook `{hook}` failed: {}", result.to_string())
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a command that runs `command_line` with the system shell. The command is killed if the build is
+/// cancelled while it runs.
+pub(crate) fn shell_command(command_line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(command_line).kill_on_drop(true);
+    command
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use semver::Version;
+
+    use super::*;
+    use crate::parser::{DevConfig, HooksConfig};
+
+    #[tokio::test]
+    async fn hooks_receive_project_env_and_fail_by_name() {
+        let project = tempfile::tempdir().expect("Unable to create tempdir");
+        let common_config = CommonConfig {
+            name: "hooked".to_string(),
+            version: Version::parse("0.2.0").unwrap(),
+            path: project.path().to_path_buf(),
+            wasm_bin_name: None,
+            hooks: HooksConfig {
+                pre_build: vec![],
+                post_build: vec![
+                    "echo \"$WASH_PROJECT_NAME $WASH_PROJECT_VERSION $WASH_ARTIFACT_PATH\" > env.txt"
+                        .to_string(),
+                ],
+                post_sign: vec!["true".to_string(), "exit 3".to_string()],
+            },
+            dev: DevConfig::default(),
+        };
+
+        run_hooks(
+            HookStage::PostBuild,
+            &common_config,
+            Some(Path::new("/tmp/hooked.wasm")),
+        )
+        .await
+        .expect("post_build hook should succeed");
+        assert_eq!(
+            std::fs::read_to_string(project.path().join("env.txt")).unwrap(),
+            "hooked 0.2.0 /tmp/hooked.wasm\n"
+        );
+
+        let err = run_hooks(HookStage::PostSign, &common_config, None)
+            .await
+            .expect_err("post_sign hook should fail");
+        assert!(err
+            .to_string()
+            .starts_with("post_sign hook `exit 3` failed"));
+    }
+}
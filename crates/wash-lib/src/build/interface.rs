@@ -0,0 +1,126 @@
+//! Build a wasmCloud interface by generating code and HTML documentation from smithy models
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, Context, Result};
+use atelier_core::model::Model;
+use walkdir::WalkDir;
+use weld_codegen::{
+    config::{CodegenConfig, ModelSource, OutputLanguage},
+    sources_to_model, Generator,
+};
+
+use super::hooks::{run_hooks, HookStage};
+use crate::parser::{CommonConfig, InterfaceConfig};
+
+/// Builds a wasmCloud interface with the same weld-codegen pipeline used by `wash gen`. Code is generated
+/// for every language listed in the interface's `codegen.toml`, and HTML documentation is rendered into
+/// [InterfaceConfig::html_target].
+///
+/// The `pre_build` and `post_build` hooks in [CommonConfig::hooks] run before and after generation, with the
+/// HTML output directory as the artifact path.
+///
+/// Returns the list of files that were created or updated by code generation.
+///
+/// # Arguments
+/// * `interface_config`: [InterfaceConfig] for the location of the `codegen.toml` file and the HTML output directory
+/// * `common_config`: [CommonConfig] specifying common parameters like [CommonConfig::path]
+pub async fn build_interface(
+    interface_config: &InterfaceConfig,
+    common_config: &CommonConfig,
+) -> Result<Vec<PathBuf>> {
+    run_hooks(HookStage::PreBuild, common_config, None).await?;
+
+    let codegen_path = common_config.path.join(&interface_config.codegen_config);
+    let mut config = fs::read_to_string(&codegen_path)
+        .with_context(|| {
+            format!(
+                "Failed to read codegen config file {}",
+                codegen_path.display()
+            )
+        })?
+        .parse::<CodegenConfig>()
+        .map_err(|e| anyhow!("{e}"))?;
+    config.base_dir = codegen_path
+        .parent()
+        .ok_or_else(|| anyhow!("Could not get parent path of codegen config file"))?
+        .canonicalize()?;
+
+    // Always render documentation, even if codegen.toml does not configure HTML output
+    let html_target = common_config.path.join(&interface_config.html_target);
+    config
+        .languages
+        .entry(OutputLanguage::Html)
+        .or_default()
+        .output_dir = html_target;
+
+    let output_dirs = config
+        .languages
+        .values()
+        .map(|lc| config.base_dir.join(&lc.output_dir))
+        .collect::<Vec<PathBuf>>();
+
+    let mut models = Vec::new();
+    std::mem::swap(&mut config.models, &mut models);
+    let base_dir = config.base_dir.clone();
+
+    let started = SystemTime::now();
+    // Loading the model and generating code are blocking
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let model = build_model(models, base_dir.clone(), 0)?;
+        Generator::default().gen(Some(&model), config, Vec::new(), &base_dir, Vec::new())?;
+        Ok(())
+    })
+    .await?
+    .context("Failed to generate interface")?;
+
+    let mut generated_files = Vec::new();
+    for dir in output_dirs {
+        generated_files.extend(files_modified_since(&dir, started));
+    }
+    generated_files.sort();
+    generated_files.dedup();
+
+    run_hooks(
+        HookStage::PostBuild,
+        common_config,
+        Some(&common_config.path.join(&interface_config.html_target)),
+    )
+    .await?;
+
+    Ok(generated_files)
+}
+
+/// Builds a smithy model from the given sources, relative to `base_dir`. Dependent models may be downloaded by a
+/// background thread.
+pub fn build_model(sources: Vec<ModelSource>, base_dir: PathBuf, verbose: u8) -> Result<Model> {
+    // The downloader crate (used by sources_to_model) creates a tokio Runtime and calls block_on(), which panics
+    // when called from within another Runtime. Using thread::spawn here allows the second Runtime.
+    std::thread::spawn(move || {
+        sources_to_model(&sources, &base_dir, verbose).map_err(|e| anyhow!("{e}"))
+    })
+    .join()
+    .map_err(|_| anyhow!("downloader thread panicked"))?
+}
+
+/// Returns all files under `dir` that were modified at or after `since`
+fn files_modified_since(dir: &Path, since: SystemTime) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .map(|modified| modified >= since)
+                .unwrap_or(false)
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
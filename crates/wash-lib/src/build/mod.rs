@@ -0,0 +1,1153 @@
+//! Build (and sign) a wasmCloud actor, provider, or interface. Depends on the "cli" feature
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use nkeys::KeyPairType;
+use once_cell::sync::Lazy;
+use provider_archive::ProviderArchive;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+    sync::{mpsc::UnboundedSender, OwnedMutexGuard},
+    task::JoinHandle,
+};
+
+use crate::cli::{
+    claims::{sign_file, ActorMetadata, GenerateCommon, SignCommand},
+    determine_directory, extract_keypair, OutputKind,
+};
+use crate::config::build_cache_dir;
+use crate::parser::{
+    is_wasi_target, ActorConfig, CommonConfig, CustomConfig, LanguageConfig, ProjectConfig,
+    ProviderConfig, Revision, RustConfig, RustProfile, TinyGoConfig, TypeConfig,
+};
+
+mod cache;
+mod hooks;
+mod interface;
+mod optimize;
+mod provenance;
+mod sbom;
+mod workspace;
+
+pub(crate) use cache::IGNORED_PROJECT_DIRS;
+use hooks::{run_hooks, shell_command, HookStage};
+pub use hooks::{ARTIFACT_PATH_ENV, PROJECT_NAME_ENV, PROJECT_VERSION_ENV};
+pub use interface::{build_interface, build_model};
+pub use optimize::{wasm_size, SectionSize, SizeReport, WasmSize};
+pub use provenance::{Provenance, BUILDER_ENV, PROVENANCE_TAG_PREFIX};
+pub use sbom::{write_sbom, SbomComponent, SbomFormat};
+pub use workspace::{
+    build_workspace, discover_projects, BuildStatus, ProjectBuildResult, WORKSPACE_FILE_NAME,
+};
+
+/// Name of the WASI preview1 module that the adapter module replaces when creating a component
+const WASI_PREVIEW1_ADAPTER_NAME: &str = "wasi_snapshot_preview1";
+
+tokio::task_local! {
+    /// Receives the output of the commands run by builds in the current task, line by line, instead of it being
+    /// written to the terminal. Set it for a task with `BUILD_OUTPUT.scope`, e.g. to show compiler output in a
+    /// pane of `wash dev`.
+    pub static BUILD_OUTPUT: UnboundedSender<String>;
+
+    /// Lock on the project being built by the current task, see [spawn_build_step]
+    static PROJECT_LOCK: Arc<OwnedMutexGuard<()>>;
+}
+
+/// Locks of the projects built by this process, by project path
+static PROJECT_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(Default::default);
+
+/// Runs a blocking step of a build, like rewriting the module, on the blocking thread pool. The step keeps holding
+/// the lock on the project until it finishes, even when the build is cancelled (e.g. by `wash dev` when files change
+/// again), so it can't overwrite the outputs of the next build of the project.
+fn spawn_build_step<F, R>(step: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let lock = PROJECT_LOCK.try_with(Arc::clone).ok();
+    tokio::task::spawn_blocking(move || {
+        let _lock = lock;
+        step()
+    })
+}
+
+/// Runs a command of a build to completion, sending its output to [`BUILD_OUTPUT`] when it is set
+pub(crate) async fn run_command(command: &mut Command) -> std::io::Result<ExitStatus> {
+    let output = match BUILD_OUTPUT.try_with(Clone::clone) {
+        Ok(output) => output,
+        Err(_) => return command.status().await,
+    };
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    futures::join!(
+        forward_lines(child.stdout.take(), &output),
+        forward_lines(child.stderr.take(), &output)
+    );
+    child.wait().await
+}
+
+async fn forward_lines(reader: Option<impl AsyncRead + Unpin>, output: &UnboundedSender<String>) {
+    if let Some(reader) = reader {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = output.send(line);
+        }
+    }
+}
+
+/// An artifact produced by building a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildArtifact {
+    /// Path to the built artifact
+    pub path: PathBuf,
+    /// Digest of the artifact's contents, in the form `sha256:<hex>`. Empty when the artifact is a directory,
+    /// like the documentation generated for an interface.
+    pub digest: String,
+    /// Size of the artifact in bytes
+    pub size: u64,
+    /// The keys the artifact was signed with, if it was signed
+    pub signing_keys: Option<SigningKeys>,
+    /// How long the build took. For an artifact restored from the build cache, this is the time it took to
+    /// restore it.
+    pub duration: Duration,
+    /// The size of the actor module before and after it was optimized, when optimization is enabled
+    pub size_report: Option<SizeReport>,
+}
+
+/// The public keys of the issuer and subject an artifact was signed with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningKeys {
+    /// Public key of the account that issued the artifact's claims
+    pub issuer: String,
+    /// Public key of the actor module or provider service
+    pub subject: String,
+}
+
+impl BuildArtifact {
+    /// Describes the artifact at `path` by reading its digest, size, and the keys it was signed with
+    async fn describe(
+        path: PathBuf,
+        size_report: Option<SizeReport>,
+        duration: Duration,
+    ) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(BuildArtifact {
+                path,
+                digest: String::new(),
+                size: 0,
+                signing_keys: None,
+                duration,
+                size_report,
+            });
+        }
+
+        let contents = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read built artifact {}", path.display()))?;
+        let signing_keys = signing_keys_of(&path, &contents).await?;
+        Ok(BuildArtifact {
+            digest: format!("sha256:{:x}", Sha256::digest(&contents)),
+            size: contents.len() as u64,
+            path,
+            signing_keys,
+            duration,
+            size_report,
+        })
+    }
+}
+
+/// Returns the keys a signed actor module or provider archive was signed with, or `None` for unsigned artifacts
+async fn signing_keys_of(path: &Path, contents: &[u8]) -> Result<Option<SigningKeys>> {
+    let is_par = path
+        .file_name()
+        .map(|name| name.to_string_lossy().ends_with(".par.gz"))
+        .unwrap_or(false);
+    if is_par {
+        let par = ProviderArchive::try_load(contents)
+            .await
+            .map_err(|e| anyhow!("Failed to load provider archive: {e}"))?;
+        return Ok(par.claims().map(|claims| SigningKeys {
+            issuer: claims.issuer,
+            subject: claims.subject,
+        }));
+    }
+    if path.extension().map(|ext| ext == "wasm").unwrap_or(false) {
+        return Ok(
+            wascap::wasm::extract_claims(contents)?.map(|token| SigningKeys {
+                issuer: token.claims.issuer,
+                subject: token.claims.subject,
+            }),
+        );
+    }
+    Ok(None)
+}
+
+/// Configuration for signing an artifact (actor or provider) including issuer and subject key, the path to where keys can be found, and an option to
+/// disable automatic key generation if keys cannot be found.
+#[derive(Debug, Clone)]
+pub struct SignConfig {
+    /// Location of key files for signing
+    pub keys_directory: Option<PathBuf>,
+
+    /// Path to issuer seed key (account). If this flag is not provided, the seed will be sourced from ($HOME/.wash/keys) or generated for you if it cannot be found.
+    pub issuer: Option<String>,
+
+    /// Path to subject seed key (module or service). If this flag is not provided, the seed will be sourced from ($HOME/.wash/keys) or generated for you if it cannot be found.
+    pub subject: Option<String>,
+
+    /// Disables autogeneration of keys if seed(s) are not provided
+    pub disable_keygen: bool,
+}
+
+/// Using a [ProjectConfig], usually parsed from a `wasmcloud.toml` file, build the project
+/// with the installed language toolchain. This will delegate to [build_actor] when the project is an actor,
+/// to [build_provider] when the project is a capability provider, or to [build_interface] when the project is an interface.
+///
+/// The `pre_build`, `post_build`, and `post_sign` hooks configured in [CommonConfig::hooks] are run before
+/// compiling, after compiling, and after signing respectively. A failing hook aborts the build.
+///
+/// Actor and provider artifacts are cached by the hash of their inputs (source files, configuration, toolchain
+/// version, and signing options), so building a project that has not changed returns the previously built artifact
+/// without invoking the toolchain or signing again. The cache can be cleared with [Drain::Build](crate::drain::Drain::Build).
+///
+/// Toolchains and hooks run as child processes with the project directory as their working directory, and the
+/// process-wide current directory is never changed, so several projects can be built concurrently.
+///
+/// This function returns the [BuildArtifact] with the path to the compiled artifact, a signed Wasm module, signed
+/// provider archive, or the directory containing generated interface documentation, along with its digest, size,
+/// signing keys, and how long the build took.
+///
+/// # Usage
+/// ```no_run
+/// use wash_lib::{build::build_project, parser::get_config};
+/// # async fn build() -> anyhow::Result<()> {
+/// let config = get_config(None, Some(true))?;
+/// let artifact = build_project(&config, None).await?;
+/// println!("Here is the signed artifact: {}", artifact.path.to_string_lossy());
+/// # Ok(())
+/// # }
+/// ```
+/// # Arguments
+/// * `config`: [ProjectConfig] for required information to find, build, and sign an actor
+/// * `signing`: Optional [SignConfig] with information for signing the project artifact. If omitted, the artifact will only be built
+///
+/// When an artifact built from the same inputs is found in the build cache, it is restored instead of being built
+/// again. The post-build and post-sign hooks still run, both against the restored artifact.
+///
+/// Builds of the same project run one at a time, so a build waits for a cancelled build of the project to stop
+/// writing its outputs.
+pub async fn build_project(
+    config: &ProjectConfig,
+    signing: Option<SignConfig>,
+) -> Result<BuildArtifact> {
+    // Builds of the same project wait for the previous one, including its blocking steps, to finish
+    let lock = PROJECT_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(config.common.path.clone())
+        .or_default()
+        .clone();
+    let lock = Arc::new(lock.lock_owned().await);
+    PROJECT_LOCK
+        .scope(lock, build_locked_project(config, signing))
+        .await
+}
+
+async fn build_locked_project(
+    config: &ProjectConfig,
+    signing: Option<SignConfig>,
+) -> Result<BuildArtifact> {
+    let started = Instant::now();
+    if let TypeConfig::Interface(interface_config) = &config.project_type {
+        build_interface(interface_config, &config.common).await?;
+        return BuildArtifact::describe(
+            config.common.path.join(&interface_config.html_target),
+            None,
+            started.elapsed(),
+        )
+        .await;
+    }
+
+    // Pre-build hooks may generate sources, so they run before the cache is checked
+    run_hooks(HookStage::PreBuild, &config.common, None).await?;
+
+    // Skip building entirely if an artifact was already built from the same inputs
+    let cache_dir = build_cache_dir()?;
+    let cache_key = {
+        let config = config.clone();
+        let signing = signing.clone();
+        // Hashing the sources and querying the toolchain version are blocking
+        tokio::task::spawn_blocking(move || cache::cache_key(&config, signing.as_ref())).await??
+    };
+    match cache::lookup(&cache_dir, &cache_key) {
+        Ok(Some(mut artifact)) => {
+            artifact.duration = started.elapsed();
+            log::debug!(
+                "Using cached artifact for {} at {}, running post-build hooks against it",
+                config.common.name,
+                artifact.path.display()
+            );
+            run_hooks(HookStage::PostBuild, &config.common, Some(&artifact.path)).await?;
+            if signing.is_some() {
+                run_hooks(HookStage::PostSign, &config.common, Some(&artifact.path)).await?;
+            }
+            return Ok(artifact);
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to look up cached build artifact: {e}"),
+    }
+
+    let mut artifact = match &config.project_type {
+        TypeConfig::Actor(actor_config) => {
+            build_actor(actor_config, &config.language, &config.common, signing).await?
+        }
+        TypeConfig::Provider(provider_config) => {
+            build_provider(provider_config, &config.language, &config.common, signing).await?
+        }
+        TypeConfig::Interface(_) => unreachable!("interfaces are built without caching"),
+    };
+    artifact.duration = started.elapsed();
+
+    if let Err(e) = cache::store(&cache_dir, &cache_key, &artifact) {
+        log::warn!("Failed to cache build artifact: {e}");
+    }
+
+    Ok(artifact)
+}
+
+/// Builds a wasmCloud actor using the installed language toolchain, then signs the actor with
+/// keys, capability claims, and additional friendly information like name, version, revision, etc.
+///
+/// The [Provenance] of the build (git commit, build time, toolchain version, and builder) is recorded as tags
+/// in the actor's claims.
+///
+/// When optimization is enabled in [ActorConfig::optimize], the compiled module is stripped and/or optimized
+/// before it is componentized and signed, and the returned [BuildArtifact] includes a [SizeReport]. The build
+/// fails if the final artifact is larger than the configured maximum size.
+///
+/// # Arguments
+/// * `actor_config`: [ActorConfig] for required information to find, build, and sign an actor
+/// * `language_config`: [LanguageConfig] specifying which language the actor is written in
+/// * `common_config`: [CommonConfig] specifying common parameters like [CommonConfig::name] and [CommonConfig::version]
+/// * `signing`: Optional [SignConfig] with information for signing the actor. If omitted, the actor will only be built
+pub async fn build_actor(
+    actor_config: &ActorConfig,
+    language_config: &LanguageConfig,
+    common_config: &CommonConfig,
+    signing_config: Option<SignConfig>,
+) -> Result<BuildArtifact> {
+    let started = Instant::now();
+    // Build actor based on language toolchain
+    let file_path = match language_config {
+        LanguageConfig::Rust(rust_config) => {
+            build_rust_actor(common_config, rust_config, actor_config).await
+        }
+        LanguageConfig::TinyGo(tinygo_config) => {
+            build_tinygo_actor(common_config, tinygo_config, actor_config).await
+        }
+        LanguageConfig::Custom(custom_config) => {
+            build_custom_actor(common_config, custom_config).await
+        }
+    }?;
+
+    let size_report = if actor_config.optimize.is_enabled() {
+        Some(optimize::optimize_module(&file_path, &actor_config.optimize).await?)
+    } else {
+        None
+    };
+
+    // Turn the core module into a component if a WASI adapter is configured
+    if let Some(adapter_path) = actor_config.wasi_adapter_path.as_ref() {
+        let module_path = file_path.clone();
+        let adapter_path = common_config.path.join(adapter_path);
+        // Encoding the component rewrites the whole module, which is blocking
+        spawn_build_step(move || componentize_module(&module_path, &adapter_path)).await??;
+    }
+
+    run_hooks(HookStage::PostBuild, common_config, Some(&file_path)).await?;
+
+    let artifact_path = if let Some(config) = signing_config {
+        let provenance = {
+            let language_config = language_config.clone();
+            let common_config = common_config.clone();
+            tokio::task::spawn_blocking(move || {
+                Provenance::collect(&language_config, &common_config)
+            })
+            .await?
+        };
+
+        let source = file_path
+            .to_str()
+            .ok_or_else(|| anyhow!("Could not convert file path to string"))?
+            .to_string();
+
+        // Output the signed file in the same directory with a _s suffix
+        let destination = source.replace(".wasm", "_s.wasm");
+        let destination_file = PathBuf::from_str(&destination)?;
+
+        // Keys given on the command line take precedence over the keys pinned in wasmcloud.toml
+        let issuer = match (config.issuer, actor_config.issuer_key.as_deref()) {
+            (Some(issuer), _) => Some(issuer),
+            (None, Some(key)) => Some(resolve_key(
+                key,
+                &common_config.path,
+                config.keys_directory.clone(),
+            )?),
+            (None, None) => None,
+        };
+        let subject = match (config.subject, actor_config.subject_key.as_deref()) {
+            (Some(subject), _) => Some(subject),
+            (None, Some(key)) => Some(resolve_key(
+                key,
+                &common_config.path,
+                config.keys_directory.clone(),
+            )?),
+            (None, None) => None,
+        };
+        let rev = match actor_config.rev {
+            Some(Revision::Number(rev)) => Some(rev),
+            Some(Revision::Auto) => Some(previous_revision(&destination_file)?.unwrap_or(0) + 1),
+            None => None,
+        };
+
+        let sign_options = SignCommand {
+            source,
+            destination: Some(destination),
+            metadata: ActorMetadata {
+                name: common_config.name.clone(),
+                ver: Some(common_config.version.to_string()),
+                custom_caps: actor_config.claims.clone(),
+                call_alias: actor_config.call_alias.clone(),
+                tags: actor_config
+                    .tags
+                    .iter()
+                    .cloned()
+                    .chain(provenance.to_tags())
+                    .collect(),
+                rev,
+                issuer,
+                subject,
+                common: GenerateCommon {
+                    directory: config.keys_directory,
+                    expires_in_days: actor_config.expires_in_days,
+                    not_before_days: actor_config.not_before_days,
+                    disable_keygen: config.disable_keygen,
+                },
+                ..Default::default()
+            },
+        };
+        {
+            let destination_file = destination_file.clone();
+            let max_size = actor_config.optimize.max_size;
+            // Embedding the claims rewrites the whole module, which is blocking
+            spawn_build_step(move || {
+                sign_file(sign_options, OutputKind::Json)?;
+                check_max_size(&destination_file, max_size)
+            })
+            .await??;
+        }
+        run_hooks(HookStage::PostSign, common_config, Some(&destination_file)).await?;
+
+        destination_file
+    } else {
+        // Exit without signing
+        check_max_size(&file_path, actor_config.optimize.max_size)?;
+        file_path
+    };
+
+    BuildArtifact::describe(artifact_path, size_report, started.elapsed()).await
+}
+
+/// Returns whether a key pinned in `wasmcloud.toml` is the path of a seed file, rather than the name of a key in
+/// the key directory
+fn is_key_path(key: &str) -> bool {
+    key.ends_with(".nk") || key.contains('/') || key.contains('\\')
+}
+
+/// Resolves a key pinned in `wasmcloud.toml` to the path of its seed file. Keys that look like paths are
+/// relative to the project directory, and anything else is the name of a key in the key directory, e.g.
+/// `myorg_account` for `~/.wash/keys/myorg_account.nk`.
+fn resolve_key(key: &str, project_path: &Path, keys_directory: Option<PathBuf>) -> Result<String> {
+    let path = if is_key_path(key) {
+        project_path.join(key)
+    } else {
+        determine_directory(keys_directory)?.join(format!("{key}.nk"))
+    };
+    if !path.is_file() {
+        bail!("Signing key {key} not found at {}", path.display())
+    }
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Could not convert key path to string"))
+}
+
+/// Returns the revision in the claims of the previously built artifact at `path`, if there is one
+fn previous_revision(path: &Path) -> Result<Option<i32>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(wascap::wasm::extract_claims(contents)?
+        .and_then(|token| token.claims.metadata)
+        .and_then(|metadata| metadata.rev))
+}
+
+/// Fails if the artifact at `path` is larger than `max_size` bytes
+fn check_max_size(path: &Path, max_size: Option<u64>) -> Result<()> {
+    let max_size = match max_size {
+        Some(max_size) => max_size,
+        None => return Ok(()),
+    };
+    let size = fs::metadata(path)?.len();
+    if size > max_size {
+        bail!(
+            "Artifact {} is {size} bytes, which exceeds the maximum size of {max_size} bytes",
+            path.display()
+        )
+    }
+    Ok(())
+}
+
+/// Returns the target directory of the cargo project at `project_path`
+async fn cargo_target_dir(project_path: &Path) -> Result<PathBuf> {
+    let mut command = cargo_metadata::MetadataCommand::new();
+    command.current_dir(project_path);
+    let metadata = tokio::task::spawn_blocking(move || command.exec()).await??;
+    Ok(metadata.target_directory.into_std_path_buf())
+}
+
+/// Builds a rust actor and returns the path to the file.
+async fn build_rust_actor(
+    common_config: &CommonConfig,
+    rust_config: &RustConfig,
+    actor_config: &ActorConfig,
+) -> Result<PathBuf> {
+    let mut command = match rust_config.cargo_path.as_ref() {
+        Some(path) => Command::new(path),
+        None => Command::new("cargo"),
+    };
+
+    let target_path = cargo_target_dir(&common_config.path).await?;
+    let profile = rust_config.build_profile();
+
+    // The compiler is killed when the build is cancelled, e.g. by `wash dev` when files change again
+    let result = run_command(
+        command
+            .args(["build", "--target", &actor_config.wasm_target])
+            .args(cargo_profile_args(&profile))
+            .current_dir(&common_config.path)
+            .kill_on_drop(true),
+    )
+    .await
+    .map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            anyhow!("{:?} command is not found", command.as_std().get_program())
+        } else {
+            anyhow!(e)
+        }
+    })?;
+
+    if !result.success() {
+        bail!("Compiling actor failed: {}", result.to_string())
+    }
+
+    // Determine the wasm binary name
+    let wasm_bin_name = common_config
+        .wasm_bin_name
+        .as_ref()
+        .unwrap_or(&common_config.name);
+
+    let wasm_file = PathBuf::from(format!(
+        "{}/{}/{}/{}.wasm",
+        rust_config
+            .target_path
+            .clone()
+            .unwrap_or(target_path)
+            .to_string_lossy(),
+        actor_config.wasm_target,
+        cargo_profile_dir(&profile),
+        wasm_bin_name,
+    ));
+
+    if !wasm_file.exists() {
+        bail!(
+            "Could not find compiled wasm file, please ensure {} exists",
+            wasm_file.display()
+        );
+    }
+
+    // move the file out into the build/ folder for parity with tinygo and convienience for users.
+    let copied_wasm_file = common_config
+        .path
+        .join(format!("build/{}.wasm", wasm_bin_name));
+    if let Some(p) = copied_wasm_file.parent() {
+        fs::create_dir_all(p)?;
+    }
+    fs::copy(&wasm_file, &copied_wasm_file)?;
+    fs::remove_file(&wasm_file)?;
+
+    // Return the full path to the compiled Wasm file
+    Ok(copied_wasm_file)
+}
+
+/// Builds a tinygo actor and returns the path to the file.
+async fn build_tinygo_actor(
+    common_config: &CommonConfig,
+    tinygo_config: &TinyGoConfig,
+    actor_config: &ActorConfig,
+) -> Result<PathBuf> {
+    let filename = format!("build/{}.wasm", common_config.name);
+
+    let mut command = match &tinygo_config.tinygo_path {
+        Some(path) => Command::new(path),
+        None => Command::new("tinygo"),
+    };
+
+    let wasm_file = common_config.path.join(&filename);
+    if let Some(p) = wasm_file.parent() {
+        fs::create_dir_all(p)?;
+    }
+
+    let profile = tinygo_config.build_profile()?;
+    command.args([
+        "build",
+        "-o",
+        filename.as_str(),
+        "-target",
+        if is_wasi_target(&actor_config.wasm_target) {
+            "wasi"
+        } else {
+            "wasm"
+        },
+        "-scheduler",
+        profile.scheduler.as_str(),
+    ]);
+    if let Some(gc) = profile.gc.as_ref() {
+        command.args(["-gc", gc]);
+    }
+    if profile.no_debug {
+        command.arg("-no-debug");
+    }
+
+    let result = run_command(
+        command
+            .args(&profile.extra_args)
+            .arg(".")
+            .current_dir(&common_config.path)
+            .kill_on_drop(true),
+    )
+    .await
+    .map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            anyhow!("{:?} command is not found", command.as_std().get_program())
+        } else {
+            anyhow!(e)
+        }
+    })?;
+
+    if !result.success() {
+        bail!("Compiling actor failed: {}", result.to_string())
+    }
+
+    if !wasm_file.exists() {
+        bail!(
+            "Could not find compiled wasm file to sign: {}",
+            wasm_file.display()
+        );
+    }
+
+    Ok(wasm_file)
+}
+
+/// Builds an actor written in any language by running the configured build command, and returns the path to
+/// the Wasm module it produced.
+async fn build_custom_actor(
+    common_config: &CommonConfig,
+    custom_config: &CustomConfig,
+) -> Result<PathBuf> {
+    let mut command = shell_command(&custom_config.build_command);
+
+    let result = run_command(
+        command
+            .current_dir(common_config.path.join(&custom_config.working_dir))
+            .envs(&custom_config.env)
+            .kill_on_drop(true),
+    )
+    .await
+    .map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            anyhow!("{:?} command is not found", command.as_std().get_program())
+        } else {
+            anyhow!(e)
+        }
+    })?;
+
+    if !result.success() {
+        bail!(
+            "Build command `{}` failed: {}",
+            custom_config.build_command,
+            result.to_string()
+        )
+    }
+
+    let wasm_file = common_config.path.join(&custom_config.output_path);
+    if !wasm_file.exists() {
+        bail!(
+            "Could not find compiled wasm file, please ensure the build command writes it to {}",
+            wasm_file.display()
+        );
+    }
+
+    Ok(wasm_file)
+}
+
+/// Converts the core module at `module_path` into a WebAssembly component in place, adapting WASI
+/// preview1 imports with the adapter module found at `adapter_path`
+fn componentize_module(module_path: &Path, adapter_path: &Path) -> Result<()> {
+    let module = fs::read(module_path)?;
+    let adapter = fs::read(adapter_path).with_context(|| {
+        format!(
+            "Failed to read WASI adapter module at {}",
+            adapter_path.display()
+        )
+    })?;
+
+    let component = wit_component::ComponentEncoder::default()
+        .module(&module)?
+        .adapter(WASI_PREVIEW1_ADAPTER_NAME, &adapter)?
+        .validate(true)
+        .encode()
+        .context("Failed to create component from core module")?;
+
+    fs::write(module_path, component)?;
+    Ok(())
+}
+
+/// Builds a wasmCloud capability provider for each configured target using the installed language
+/// toolchain, then assembles the binaries into a provider archive signed with the issuer and subject keys.
+/// The compressed archive is written to `build/{name}.par.gz` in the project directory.
+///
+/// A provider archive must be signed, so if signing is omitted the provider binaries are compiled without
+/// creating an archive and the binary built for the first target is returned instead.
+///
+/// # Arguments
+/// * `provider_config`: [ProviderConfig] for required information to find, build, and sign a provider
+/// * `language_config`: [LanguageConfig] specifying which language the provider is written in
+/// * `common_config`: [CommonConfig] specifying common parameters like [CommonConfig::name] and [CommonConfig::version]
+/// * `signing`: Optional [SignConfig] with information for signing the provider archive
+pub async fn build_provider(
+    provider_config: &ProviderConfig,
+    language_config: &LanguageConfig,
+    common_config: &CommonConfig,
+    signing_config: Option<SignConfig>,
+) -> Result<BuildArtifact> {
+    let started = Instant::now();
+    let binaries = match language_config {
+        LanguageConfig::Rust(rust_config) => {
+            build_rust_provider(common_config, rust_config, provider_config).await
+        }
+        LanguageConfig::TinyGo(_) => {
+            bail!("wash build has not been implemented for TinyGo providers yet")
+        }
+        LanguageConfig::Custom(_) => {
+            bail!("wash build has not been implemented for custom language providers yet")
+        }
+    }?;
+
+    for (_, binary_path) in binaries.iter() {
+        run_hooks(HookStage::PostBuild, common_config, Some(binary_path)).await?;
+    }
+
+    let config = match signing_config {
+        Some(config) => config,
+        None => {
+            // Exit without creating an archive
+            let binary_path = binaries
+                .into_iter()
+                .next()
+                .map(|(_, path)| path)
+                .ok_or_else(|| anyhow!("No provider binaries were built"))?;
+            return BuildArtifact::describe(binary_path, None, started.elapsed()).await;
+        }
+    };
+
+    let mut par = ProviderArchive::new(
+        &provider_config.capability_id,
+        &common_config.name,
+        &provider_config.vendor,
+        None,
+        Some(common_config.version.to_string()),
+    );
+
+    for (par_target, binary_path) in binaries.iter() {
+        let lib = fs::read(binary_path).with_context(|| {
+            format!(
+                "Failed to read provider binary at {}",
+                binary_path.display()
+            )
+        })?;
+        par.add_library(par_target, &lib)
+            .map_err(|e| anyhow!("{e}"))
+            .with_context(|| format!("Failed to add {par_target} binary to provider archive"))?;
+    }
+
+    let issuer = extract_keypair(
+        config.issuer,
+        Some(common_config.name.clone()),
+        config.keys_directory.clone(),
+        KeyPairType::Account,
+        config.disable_keygen,
+        OutputKind::Json,
+    )?;
+    let subject = extract_keypair(
+        config.subject,
+        Some(common_config.name.clone()),
+        config.keys_directory,
+        KeyPairType::Service,
+        config.disable_keygen,
+        OutputKind::Json,
+    )?;
+
+    let destination = common_config
+        .path
+        .join(format!("build/{}.par.gz", common_config.name));
+    if let Some(p) = destination.parent() {
+        fs::create_dir_all(p)?;
+    }
+
+    par.write(&destination, &issuer, &subject, true)
+        .await
+        .map_err(|e| anyhow!("{e}"))
+        .with_context(|| {
+            format!(
+                "Failed to write provider archive to {}",
+                destination.display()
+            )
+        })?;
+    run_hooks(HookStage::PostSign, common_config, Some(&destination)).await?;
+
+    BuildArtifact::describe(destination, None, started.elapsed()).await
+}
+
+/// Builds a rust provider for each configured target triple. Returns a list of the provider archive
+/// target (in `ARCH-OS` format) and the path to the binary built for it.
+async fn build_rust_provider(
+    common_config: &CommonConfig,
+    rust_config: &RustConfig,
+    provider_config: &ProviderConfig,
+) -> Result<Vec<(String, PathBuf)>> {
+    let target_path = match rust_config.target_path.clone() {
+        Some(target_path) => target_path,
+        None => cargo_target_dir(&common_config.path).await?,
+    };
+
+    let bin_name = provider_config
+        .bin_name
+        .as_ref()
+        .unwrap_or(&common_config.name);
+    let profile = rust_config.build_profile();
+
+    // Without any configured targets, build for the host
+    let targets: Vec<Option<&str>> = if provider_config.targets.is_empty() {
+        vec![None]
+    } else {
+        provider_config
+            .targets
+            .iter()
+            .map(|t| Some(t.as_str()))
+            .collect()
+    };
+
+    let mut binaries = Vec::with_capacity(targets.len());
+    for target in targets {
+        let mut command = match rust_config.cargo_path.as_ref() {
+            Some(path) => Command::new(path),
+            None => Command::new("cargo"),
+        };
+        command
+            .arg("build")
+            .args(cargo_profile_args(&profile))
+            .current_dir(&common_config.path)
+            .kill_on_drop(true);
+        if let Some(triple) = target {
+            command.args(["--target", triple]);
+        }
+
+        let result = run_command(&mut command).await.map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                anyhow!("{:?} command is not found", command.as_std().get_program())
+            } else {
+                anyhow!(e)
+            }
+        })?;
+
+        if !result.success() {
+            bail!(
+                "Compiling provider for {} failed: {}",
+                target.unwrap_or("host target"),
+                result.to_string()
+            )
+        }
+
+        let (par_target, release_dir, exe_suffix) = match target {
+            Some(triple) => (
+                par_target_from_triple(triple)?,
+                target_path.join(triple).join(cargo_profile_dir(&profile)),
+                if triple.contains("windows") {
+                    ".exe"
+                } else {
+                    ""
+                },
+            ),
+            None => (
+                format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+                target_path.join(cargo_profile_dir(&profile)),
+                std::env::consts::EXE_SUFFIX,
+            ),
+        };
+
+        let binary_path = release_dir.join(format!("{bin_name}{exe_suffix}"));
+        if !binary_path.exists() {
+            bail!(
+                "Could not find compiled provider binary, please ensure {} exists",
+                binary_path.display()
+            );
+        }
+
+        binaries.push((par_target, binary_path));
+    }
+
+    Ok(binaries)
+}
+
+/// Returns the arguments to pass to `cargo build` to build with the given profile
+fn cargo_profile_args(profile: &RustProfile) -> Vec<String> {
+    let mut args = match profile.cargo_profile.as_str() {
+        "release" => vec!["--release".to_string()],
+        "dev" => vec![],
+        cargo_profile => vec!["--profile".to_string(), cargo_profile.to_string()],
+    };
+    if profile.no_default_features {
+        args.push("--no-default-features".to_string());
+    }
+    if !profile.features.is_empty() {
+        args.push("--features".to_string());
+        args.push(profile.features.join(","));
+    }
+    args.extend(profile.extra_args.iter().cloned());
+    args
+}
+
+/// Returns the name of the directory, inside of the cargo target directory, that artifacts built with the
+/// given profile are written to
+fn cargo_profile_dir(profile: &RustProfile) -> &str {
+    match profile.cargo_profile.as_str() {
+        "dev" | "test" => "debug",
+        "release" | "bench" => "release",
+        cargo_profile => cargo_profile,
+    }
+}
+
+/// Converts a rust target triple (e.g. `x86_64-unknown-linux-gnu`) into the `ARCH-OS` format used for
+/// provider archive targets (e.g. `x86_64-linux`)
+fn par_target_from_triple(triple: &str) -> Result<String> {
+    let arch = triple
+        .split('-')
+        .next()
+        .filter(|arch| !arch.is_empty())
+        .ok_or_else(|| anyhow!("Invalid target triple: {triple}"))?;
+
+    let os = if triple.contains("linux") {
+        "linux"
+    } else if triple.contains("darwin") {
+        "macos"
+    } else if triple.contains("windows") {
+        "windows"
+    } else {
+        bail!("Unsupported target triple for capability providers: {triple}")
+    };
+
+    Ok(format!("{arch}-{os}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_convert_target_triples_to_par_targets() {
+        assert_eq!(
+            par_target_from_triple("x86_64-unknown-linux-gnu").unwrap(),
+            "x86_64-linux"
+        );
+        assert_eq!(
+            par_target_from_triple("aarch64-unknown-linux-musl").unwrap(),
+            "aarch64-linux"
+        );
+        assert_eq!(
+            par_target_from_triple("aarch64-apple-darwin").unwrap(),
+            "aarch64-macos"
+        );
+        assert_eq!(
+            par_target_from_triple("x86_64-pc-windows-msvc").unwrap(),
+            "x86_64-windows"
+        );
+        assert!(par_target_from_triple("wasm32-unknown-unknown").is_err());
+    }
+
+    #[tokio::test]
+    async fn can_describe_unsigned_artifacts() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir");
+        let path = dir.path().join("actor.wasm");
+        fs::write(&path, b"\0asm\x01\0\0\0").unwrap();
+
+        let artifact = BuildArtifact::describe(path.clone(), None, Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(artifact.path, path);
+        assert_eq!(artifact.size, 8);
+        assert_eq!(
+            artifact.digest,
+            "sha256:93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert_eq!(artifact.signing_keys, None);
+        assert_eq!(artifact.duration, Duration::from_secs(1));
+
+        let interface = BuildArtifact::describe(dir.path().to_path_buf(), None, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(interface.digest, "");
+        assert_eq!(interface.size, 0);
+    }
+
+    #[test]
+    fn can_resolve_pinned_keys() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir");
+        let keys = dir.path().join("keys");
+        fs::create_dir(&keys).unwrap();
+        fs::write(keys.join("myorg_account.nk"), "SAAEXAMPLE").unwrap();
+
+        assert_eq!(
+            resolve_key("myorg_account", Path::new("/unused"), Some(keys.clone())).unwrap(),
+            keys.join("myorg_account.nk").to_str().unwrap()
+        );
+        assert_eq!(
+            resolve_key("keys/myorg_account.nk", dir.path(), None).unwrap(),
+            dir.path().join("keys/myorg_account.nk").to_str().unwrap()
+        );
+        assert!(resolve_key("missing", dir.path(), Some(keys)).is_err());
+    }
+
+    #[test]
+    fn unbuilt_artifacts_have_no_previous_revision() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir");
+        let path = dir.path().join("actor_s.wasm");
+        assert_eq!(previous_revision(&path).unwrap(), None);
+
+        fs::write(&path, b"\0asm\x01\0\0\0").unwrap();
+        assert_eq!(previous_revision(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn can_build_cargo_args_for_profiles() {
+        let release = RustProfile {
+            cargo_profile: "release".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(cargo_profile_args(&release), vec!["--release"]);
+        assert_eq!(cargo_profile_dir(&release), "release");
+
+        let dev = RustProfile {
+            cargo_profile: "dev".to_string(),
+            features: vec!["logging".to_string(), "metrics".to_string()],
+            no_default_features: true,
+            extra_args: vec!["--locked".to_string()],
+        };
+        assert_eq!(
+            cargo_profile_args(&dev),
+            vec![
+                "--no-default-features",
+                "--features",
+                "logging,metrics",
+                "--locked"
+            ]
+        );
+        assert_eq!(cargo_profile_dir(&dev), "debug");
+
+        let custom = RustProfile {
+            cargo_profile: "release-small".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            cargo_profile_args(&custom),
+            vec!["--profile", "release-small"]
+        );
+        assert_eq!(cargo_profile_dir(&custom), "release-small");
+    }
+
+    #[tokio::test]
+    async fn can_capture_build_output() {
+        let (output, mut lines) = tokio::sync::mpsc::unbounded_channel();
+        let status = BUILD_OUTPUT
+            .scope(
+                output,
+                run_command(&mut shell_command("echo compiling && echo warning >&2")),
+            )
+            .await
+            .expect("should be able to run command");
+        assert!(status.success());
+
+        let mut captured = Vec::new();
+        while let Ok(line) = lines.try_recv() {
+            captured.push(line);
+        }
+        captured.sort();
+        assert_eq!(captured, ["compiling", "warning"]);
+    }
+
+    #[tokio::test]
+    async fn cancelled_build_steps_hold_the_project_lock() {
+        let lock = Arc::new(tokio::sync::Mutex::new(()));
+        let (started, step_started) = std::sync::mpsc::channel();
+        let (finish, step_finish) = std::sync::mpsc::channel::<()>();
+        let build = PROJECT_LOCK.scope(Arc::new(lock.clone().lock_owned().await), async move {
+            spawn_build_step(move || {
+                started.send(()).unwrap();
+                step_finish.recv().unwrap();
+            })
+            .await
+        });
+
+        // Cancel the build while its step is running
+        let build = tokio::spawn(build);
+        tokio::task::spawn_blocking(move || step_started.recv().unwrap())
+            .await
+            .unwrap();
+        build.abort();
+        assert!(build.await.unwrap_err().is_cancelled());
+        assert!(lock.try_lock().is_err());
+
+        finish.send(()).unwrap();
+        let _guard = tokio::time::timeout(Duration::from_secs(5), lock.lock())
+            .await
+            .expect("the lock should be released once the step finishes");
+    }
+}
@@ -0,0 +1,290 @@
+//! Shrink compiled actor modules before they are signed, by stripping custom sections and running an
+//! external optimizer like Binaryen's `wasm-opt`
+
+use std::{fs, io::ErrorKind, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::{run_command, spawn_build_step};
+use crate::parser::OptimizeConfig;
+
+/// Prefix of the custom sections that describe a module's component type. These are required to turn the
+/// module into a component, so they are never stripped.
+const COMPONENT_TYPE_SECTION_PREFIX: &str = "component-type";
+
+/// The size of a Wasm binary, in bytes, and of each of its sections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmSize {
+    /// Total size of the binary
+    pub total: u64,
+    /// Size of each section, in the order they appear. Custom sections are named `custom:<name>`.
+    pub sections: Vec<SectionSize>,
+}
+
+/// The size, in bytes, of a section of a Wasm binary. Sections that appear multiple times are combined.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSize {
+    /// Name of the section
+    pub name: String,
+    /// Size of the section, including its header
+    pub size: u64,
+}
+
+/// The size of an actor module before and after it was optimized
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeReport {
+    /// Size of the module as produced by the compiler
+    pub before: WasmSize,
+    /// Size of the module after it was optimized
+    pub after: WasmSize,
+}
+
+/// Optimizes the Wasm module at `module_path` in place according to the [OptimizeConfig], returning a report
+/// of its size before and after.
+pub(crate) async fn optimize_module(
+    module_path: &Path,
+    config: &OptimizeConfig,
+) -> Result<SizeReport> {
+    let before = {
+        let module_path = module_path.to_path_buf();
+        let strip = config.strip;
+        // Parsing and rewriting the module is blocking
+        spawn_build_step(move || {
+            let original = fs::read(&module_path)?;
+            if strip {
+                fs::write(&module_path, strip_custom_sections(&original)?)?;
+            }
+            wasm_size(&original)
+        })
+        .await??
+    };
+
+    if config.wasm_opt {
+        let mut command = Command::new(
+            config
+                .wasm_opt_path
+                .clone()
+                .unwrap_or_else(|| "wasm-opt".into()),
+        );
+        let result = run_command(
+            command
+                .args(&config.wasm_opt_args)
+                .arg(module_path)
+                .arg("-o")
+                .arg(module_path)
+                .kill_on_drop(true),
+        )
+        .await
+        .map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                anyhow!(
+                    "{:?} command is not found, install binaryen or set wasm_opt_path",
+                    command.as_std().get_program()
+                )
+            } else {
+                anyhow!(e)
+            }
+        })?;
+        if !result.success() {
+            bail!(
+                "Optimizing actor with wasm-opt failed: {}",
+                result.to_string()
+            )
+        }
+    }
+
+    let after = wasm_size(&fs::read(module_path)?)?;
+    Ok(SizeReport { before, after })
+}
+
+/// Returns the size of a Wasm binary, broken down by section
+pub fn wasm_size(wasm: &[u8]) -> Result<WasmSize> {
+    let is_component = is_component(wasm)?;
+    let mut sections: Vec<SectionSize> = Vec::new();
+    for section in sections_of(wasm)? {
+        let name = match section.custom_name {
+            Some(custom_name) => format!("custom:{custom_name}"),
+            None if is_component => format!("component section {}", section.id),
+            None => core_section_name(section.id).to_string(),
+        };
+        let size = section.bytes.len() as u64;
+        match sections.iter_mut().find(|s| s.name == name) {
+            Some(existing) => existing.size += size,
+            None => sections.push(SectionSize { name, size }),
+        }
+    }
+
+    Ok(WasmSize {
+        total: wasm.len() as u64,
+        sections,
+    })
+}
+
+/// Removes every custom section, including names and DWARF debug information, from a core Wasm module.
+/// Sections describing the module's component type are kept.
+fn strip_custom_sections(wasm: &[u8]) -> Result<Vec<u8>> {
+    if is_component(wasm)? {
+        bail!("Stripping custom sections is only supported for core WebAssembly modules")
+    }
+
+    let mut stripped = wasm[..8].to_vec();
+    for section in sections_of(wasm)? {
+        let keep = match section.custom_name {
+            Some(name) => name.starts_with(COMPONENT_TYPE_SECTION_PREFIX),
+            None => true,
+        };
+        if keep {
+            stripped.extend_from_slice(section.bytes);
+        }
+    }
+    Ok(stripped)
+}
+
+/// A top-level section of a Wasm binary
+struct Section<'a> {
+    /// The section ID
+    id: u8,
+    /// The name of the section, if it's a custom section
+    custom_name: Option<String>,
+    /// The bytes of the section, including the ID and size
+    bytes: &'a [u8],
+}
+
+/// Returns whether the Wasm binary is a component rather than a core module
+fn is_component(wasm: &[u8]) -> Result<bool> {
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        bail!("Not a valid WebAssembly binary")
+    }
+    // The layer field, following the version, is 1 for components
+    Ok(wasm[6..8] != [0, 0])
+}
+
+/// Splits a Wasm binary into its top-level sections
+fn sections_of(wasm: &[u8]) -> Result<Vec<Section<'_>>> {
+    let mut sections = Vec::new();
+    let mut offset = 8;
+    while offset < wasm.len() {
+        let start = offset;
+        let id = wasm[offset];
+        offset += 1;
+        let size = read_u32_leb128(wasm, &mut offset)? as usize;
+        let contents_start = offset;
+        offset = offset
+            .checked_add(size)
+            .filter(|end| *end <= wasm.len())
+            .ok_or_else(|| {
+                anyhow!("Section at offset {start} extends past the end of the binary")
+            })?;
+
+        let custom_name = if id == 0 {
+            let mut name_offset = contents_start;
+            let name_len = read_u32_leb128(wasm, &mut name_offset)? as usize;
+            let name = wasm
+                .get(name_offset..name_offset + name_len)
+                .ok_or_else(|| anyhow!("Invalid custom section name at offset {start}"))?;
+            Some(String::from_utf8_lossy(name).to_string())
+        } else {
+            None
+        };
+
+        sections.push(Section {
+            id,
+            custom_name,
+            bytes: &wasm[start..offset],
+        });
+    }
+    Ok(sections)
+}
+
+/// Reads an unsigned LEB128 encoded integer at `offset`, advancing the offset past it
+fn read_u32_leb128(wasm: &[u8], offset: &mut usize) -> Result<u32> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *wasm
+            .get(*offset)
+            .context("Unexpected end of WebAssembly binary")?;
+        *offset += 1;
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    bail!("Invalid LEB128 integer in WebAssembly binary")
+}
+
+fn core_section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A module with a type section, a `name` custom section, and a `component-type` custom section
+    fn test_module() -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // type section with a single `() -> ()` function type
+        wasm.extend_from_slice(&[1, 4, 1, 0x60, 0, 0]);
+        // custom section "name" with 2 bytes of content
+        wasm.extend_from_slice(&[0, 7, 4, b'n', b'a', b'm', b'e', 0, 0]);
+        // custom section "component-type:x" with 1 byte of content
+        wasm.extend_from_slice(&[0, 18, 16]);
+        wasm.extend_from_slice(b"component-type:x");
+        wasm.push(0);
+        wasm
+    }
+
+    #[test]
+    fn can_report_section_sizes() {
+        let size = wasm_size(&test_module()).unwrap();
+        assert_eq!(size.total, 43);
+        assert_eq!(
+            size.sections,
+            vec![
+                SectionSize {
+                    name: "type".to_string(),
+                    size: 6
+                },
+                SectionSize {
+                    name: "custom:name".to_string(),
+                    size: 9
+                },
+                SectionSize {
+                    name: "custom:component-type:x".to_string(),
+                    size: 20
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_custom_sections_except_component_types() {
+        let stripped = strip_custom_sections(&test_module()).unwrap();
+        let names: Vec<String> = wasm_size(&stripped)
+            .unwrap()
+            .sections
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["type", "custom:component-type:x"]);
+
+        assert!(strip_custom_sections(b"not wasm").is_err());
+    }
+}
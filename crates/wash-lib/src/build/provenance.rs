@@ -0,0 +1,188 @@
+//! Record where and how an actor was built as tags in its signed claims
+
+use std::{path::Path, process::Command};
+
+use serde::Serialize;
+
+use crate::parser::{CommonConfig, LanguageConfig};
+
+/// Prefix of the claims tags that hold build provenance
+pub const PROVENANCE_TAG_PREFIX: &str = "wash.build.";
+
+/// Environment variable naming the builder recorded in build provenance. The builder isn't recorded when it is
+/// unset, so that user and host names don't end up in published artifacts
+pub const BUILDER_ENV: &str = "WASH_BUILDER";
+
+const COMMIT_TAG: &str = "commit";
+const DIRTY_TAG: &str = "dirty";
+const TIME_TAG: &str = "time";
+const TOOLCHAIN_TAG: &str = "toolchain";
+const BUILDER_TAG: &str = "builder";
+
+/// Information about where and how an artifact was built
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    /// The git commit the project was built from
+    pub git_commit: Option<String>,
+    /// Whether the project directory had uncommitted changes when it was built
+    pub git_dirty: Option<bool>,
+    /// When the artifact was built, in RFC 3339 format
+    pub build_time: Option<String>,
+    /// Version of the compiler used to build the artifact, e.g. the output of `rustc --version`
+    pub toolchain: Option<String>,
+    /// Who built the artifact, from [BUILDER_ENV]
+    pub builder: Option<String>,
+}
+
+impl Provenance {
+    /// Collects the provenance of a build of the project that is happening now
+    pub fn collect(language_config: &LanguageConfig, common_config: &CommonConfig) -> Self {
+        let (git_commit, git_dirty) = match git_state(&common_config.path) {
+            Some((commit, dirty)) => (Some(commit), Some(dirty)),
+            None => (None, None),
+        };
+
+        Provenance {
+            git_commit,
+            git_dirty,
+            build_time: Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            toolchain: compiler_version(language_config, common_config),
+            builder: builder_identity(),
+        }
+    }
+
+    /// Returns the provenance as claims tags, in the form `wash.build.<field>=<value>`
+    pub fn to_tags(&self) -> Vec<String> {
+        [
+            (COMMIT_TAG, self.git_commit.clone()),
+            (DIRTY_TAG, self.git_dirty.map(|dirty| dirty.to_string())),
+            (TIME_TAG, self.build_time.clone()),
+            (TOOLCHAIN_TAG, self.toolchain.clone()),
+            (BUILDER_TAG, self.builder.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(tag, value)| value.map(|v| format!("{PROVENANCE_TAG_PREFIX}{tag}={v}")))
+        .collect()
+    }
+
+    /// Parses provenance from claims tags, returning `None` if the tags don't contain any
+    pub fn from_tags(tags: &[String]) -> Option<Self> {
+        let mut provenance = Provenance::default();
+        let mut found = false;
+        for (tag, value) in tags.iter().filter_map(|t| {
+            t.strip_prefix(PROVENANCE_TAG_PREFIX)
+                .and_then(|t| t.split_once('='))
+        }) {
+            found = true;
+            let value = Some(value.to_string());
+            match tag {
+                COMMIT_TAG => provenance.git_commit = value,
+                DIRTY_TAG => provenance.git_dirty = value.map(|v| v == "true"),
+                TIME_TAG => provenance.build_time = value,
+                TOOLCHAIN_TAG => provenance.toolchain = value,
+                BUILDER_TAG => provenance.builder = value,
+                _ => {}
+            }
+        }
+        found.then_some(provenance)
+    }
+}
+
+/// Returns the commit checked out in the git repository containing `project_path`, and whether the project
+/// directory has uncommitted changes. Returns `None` if the project isn't in a git repository.
+pub(crate) fn git_state(project_path: &Path) -> Option<(String, bool)> {
+    let commit = command_output(
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(project_path),
+    )?;
+    // Build outputs written into the project directory don't make it dirty
+    let status = command_output(
+        Command::new("git")
+            .args([
+                "status",
+                "--porcelain",
+                "--",
+                ".",
+                ":(exclude)build",
+                ":(exclude)target",
+            ])
+            .current_dir(project_path),
+    )
+    .unwrap_or_default();
+    Some((commit, !status.is_empty()))
+}
+
+/// Returns the version of the compiler used for the project's language
+fn compiler_version(
+    language_config: &LanguageConfig,
+    common_config: &CommonConfig,
+) -> Option<String> {
+    let mut command = match language_config {
+        LanguageConfig::Rust(_) => {
+            let mut command = Command::new("rustc");
+            command.arg("--version");
+            command
+        }
+        LanguageConfig::TinyGo(tinygo_config) => {
+            let mut command = Command::new(
+                tinygo_config
+                    .tinygo_path
+                    .clone()
+                    .unwrap_or_else(|| "tinygo".into()),
+            );
+            command.arg("version");
+            command
+        }
+        LanguageConfig::Custom(_) => return None,
+    };
+    // The toolchain may be selected per directory (e.g. with a rust-toolchain.toml file)
+    command_output(command.current_dir(&common_config.path))
+}
+
+/// Returns the identity of whoever is building, when it is given with [BUILDER_ENV]
+fn builder_identity() -> Option<String> {
+    std::env::var(BUILDER_ENV)
+        .ok()
+        .filter(|builder| !builder.is_empty())
+}
+
+/// Runs the command and returns its trimmed stdout, if it ran successfully
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_round_trip_provenance_tags() {
+        let provenance = Provenance {
+            git_commit: Some("0f90fbf".to_string()),
+            git_dirty: Some(true),
+            build_time: Some("2023-06-01T12:00:00Z".to_string()),
+            toolchain: Some("rustc 1.70.0 (90c541806 2023-05-31)".to_string()),
+            builder: None,
+        };
+        let tags = provenance.to_tags();
+        assert_eq!(
+            tags,
+            vec![
+                "wash.build.commit=0f90fbf",
+                "wash.build.dirty=true",
+                "wash.build.time=2023-06-01T12:00:00Z",
+                "wash.build.toolchain=rustc 1.70.0 (90c541806 2023-05-31)",
+            ]
+        );
+
+        let mut all_tags = vec!["release".to_string()];
+        all_tags.extend(tags);
+        assert_eq!(Provenance::from_tags(&all_tags), Some(provenance));
+        assert_eq!(Provenance::from_tags(&["release".to_string()]), None);
+    }
+}
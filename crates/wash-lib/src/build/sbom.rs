@@ -0,0 +1,748 @@
+//! Generate a software bill of materials (SBOM) for built actors and providers, in CycloneDX or SPDX JSON
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Node, Package, PackageId};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::BuildArtifact;
+use crate::parser::{go_requirements, LanguageConfig, ProjectConfig, TypeConfig};
+use crate::registry::{CYCLONEDX_MEDIA_TYPE, SPDX_MEDIA_TYPE};
+
+/// Name of the CycloneDX property and SPDX external reference recording the claims subject of the artifact
+const SUBJECT_PROPERTY: &str = "wasmcloud:subject";
+/// Name of the CycloneDX property and SPDX external reference recording the claims issuer of the artifact
+const ISSUER_PROPERTY: &str = "wasmcloud:issuer";
+
+/// The format of a generated SBOM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    /// CycloneDX 1.4 JSON
+    CycloneDx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
+impl SbomFormat {
+    /// Returns the extension appended to the artifact's path to name the SBOM file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+        }
+    }
+
+    /// Returns the media type of SBOMs in this format
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => CYCLONEDX_MEDIA_TYPE,
+            SbomFormat::Spdx => SPDX_MEDIA_TYPE,
+        }
+    }
+}
+
+impl FromStr for SbomFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "cyclonedx" => Ok(SbomFormat::CycloneDx),
+            "spdx" => Ok(SbomFormat::Spdx),
+            _ => bail!("Unknown SBOM format {s}, expected one of: cyclonedx, spdx"),
+        }
+    }
+}
+
+impl fmt::Display for SbomFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SbomFormat::CycloneDx => write!(f, "cyclonedx"),
+            SbomFormat::Spdx => write!(f, "spdx"),
+        }
+    }
+}
+
+/// A package that was built into an artifact
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SbomComponent {
+    /// Name of the package, e.g. `serde` or `github.com/wasmcloud/actor-tinygo`
+    pub name: String,
+    /// Version of the package
+    pub version: String,
+    /// Package URL identifying the package, e.g. `pkg:cargo/serde@1.0.0`
+    pub purl: String,
+    /// The hex encoded SHA-256 checksum of the package, when known
+    pub sha256: Option<String>,
+}
+
+/// Writes an SBOM for the artifact built from the project to a file next to the artifact, named after the
+/// artifact with the format's [extension](SbomFormat::extension) appended, and returns its path.
+///
+/// The components of a rust project are the packages it depends on through normal dependencies, for the targets
+/// it is built for, as resolved by `cargo metadata`. Those of a tinygo project come from its `go.sum` (or `go.mod`
+/// when there is no `go.sum`). The artifact's digest and the subject of its claims are recorded in the SBOM.
+pub async fn write_sbom(
+    config: &ProjectConfig,
+    artifact: &BuildArtifact,
+    format: SbomFormat,
+) -> Result<PathBuf> {
+    let project_config = config.clone();
+    let components =
+        tokio::task::spawn_blocking(move || project_components(&project_config)).await??;
+    let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let document = match format {
+        SbomFormat::CycloneDx => cyclonedx_document(config, artifact, &components, &timestamp),
+        SbomFormat::Spdx => spdx_document(config, artifact, &components, &timestamp),
+    };
+
+    let mut file_name = artifact
+        .path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid artifact path {}", artifact.path.display()))?
+        .to_os_string();
+    file_name.push(format!(".{}", format.extension()));
+    let path = artifact.path.with_file_name(file_name);
+    tokio::fs::write(&path, serde_json::to_vec_pretty(&document)?).await?;
+    Ok(path)
+}
+
+/// Returns the packages built into the project, sorted by name and version
+fn project_components(config: &ProjectConfig) -> Result<Vec<SbomComponent>> {
+    match &config.language {
+        LanguageConfig::Rust(_) => rust_components(&config.common.path, &rust_targets(config)?),
+        LanguageConfig::TinyGo(_) => go_components(&config.common.path),
+        LanguageConfig::Custom(_) => {
+            bail!("SBOMs can only be generated for rust and tinygo projects")
+        }
+    }
+}
+
+/// Returns the target triples the artifact of a rust project is built for
+fn rust_targets(config: &ProjectConfig) -> Result<Vec<String>> {
+    match &config.project_type {
+        TypeConfig::Actor(actor_config) => Ok(vec![actor_config.wasm_target.clone()]),
+        TypeConfig::Provider(provider_config) if !provider_config.targets.is_empty() => {
+            Ok(provider_config.targets.clone())
+        }
+        _ => Ok(vec![host_target()?]),
+    }
+}
+
+/// Returns the target triple of the host, which providers are built for when they don't configure any targets
+fn host_target() -> Result<String> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .context("Failed to run rustc")?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|host| host.trim().to_string())
+        .ok_or_else(|| anyhow!("Failed to determine the host target of rustc"))
+}
+
+#[derive(Debug, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    checksum: Option<String>,
+}
+
+fn rust_components(project_path: &Path, targets: &[String]) -> Result<Vec<SbomComponent>> {
+    let manifest_path = project_path.join("Cargo.toml");
+    // Dependencies are only resolved for the targets the artifact is built for
+    let metadata = MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .other_options(
+            targets
+                .iter()
+                .flat_map(|target| ["--filter-platform".to_string(), target.clone()])
+                .collect::<Vec<_>>(),
+        )
+        .exec()
+        .context("Failed to read cargo metadata")?;
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| package.manifest_path == manifest_path)
+        .ok_or_else(|| anyhow!("Missing package information in {}", manifest_path.display()))?;
+
+    let lockfile_path = metadata.workspace_root.join("Cargo.lock");
+    let lockfile = fs::read_to_string(&lockfile_path)
+        .with_context(|| format!("Failed to read {lockfile_path}"))?;
+    let checksums = locked_checksums(&lockfile)?;
+
+    let mut components: Vec<SbomComponent> = normal_dependencies(&metadata, &package.id)?
+        .into_iter()
+        .map(|package| {
+            let version = package.version.to_string();
+            let checksum = checksums
+                .get(&(package.name.clone(), version.clone()))
+                .cloned();
+            cargo_component(
+                &package.name,
+                &version,
+                package.source.as_ref().map(|source| source.repr.as_str()),
+                checksum,
+            )
+        })
+        .collect();
+    components.sort();
+    Ok(components)
+}
+
+/// Returns the packages that the root package depends on, directly or transitively, through normal dependencies.
+/// Dev and build dependencies are not built into the artifact, so they are left out along with their own
+/// dependencies.
+fn normal_dependencies<'a>(metadata: &'a Metadata, root: &PackageId) -> Result<Vec<&'a Package>> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| anyhow!("Missing dependency resolution in cargo metadata"))?;
+    let nodes: HashMap<&PackageId, &Node> =
+        resolve.nodes.iter().map(|node| (&node.id, node)).collect();
+
+    let mut seen = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        let node = nodes
+            .get(id)
+            .ok_or_else(|| anyhow!("Package {id} is missing from the resolved dependencies"))?;
+        for dependency in node.deps.iter().filter(|dependency| {
+            dependency
+                .dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal)
+        }) {
+            if seen.insert(&dependency.pkg) {
+                queue.push_back(&dependency.pkg);
+            }
+        }
+    }
+    seen.remove(root);
+
+    Ok(metadata
+        .packages
+        .iter()
+        .filter(|package| seen.contains(&package.id))
+        .collect())
+}
+
+/// Returns the checksums of the packages in a `Cargo.lock` file, by name and version
+fn locked_checksums(lockfile: &str) -> Result<HashMap<(String, String), String>> {
+    let lockfile: Lockfile = toml::from_str(lockfile)?;
+    Ok(lockfile
+        .package
+        .into_iter()
+        .filter_map(|package| Some(((package.name, package.version), package.checksum?)))
+        .collect())
+}
+
+/// Describes a cargo package, identified by a package URL that records its git source when it has one
+fn cargo_component(
+    name: &str,
+    version: &str,
+    source: Option<&str>,
+    checksum: Option<String>,
+) -> SbomComponent {
+    let mut purl = format!("pkg:cargo/{name}@{version}");
+    // Git sources look like `git+<url>?branch=main#<commit>`
+    if let Some(git) = source.and_then(|source| source.strip_prefix("git+")) {
+        let (url, commit) = git.split_once('#').unwrap_or((git, ""));
+        let url = url.split('?').next().unwrap_or(url).replace(':', "%3A");
+        purl.push_str(&format!("?vcs_url=git%2B{url}"));
+        if !commit.is_empty() {
+            purl.push_str(&format!("%40{commit}"));
+        }
+    }
+    SbomComponent {
+        name: name.to_string(),
+        version: version.to_string(),
+        purl,
+        sha256: checksum,
+    }
+}
+
+fn go_components(project_path: &Path) -> Result<Vec<SbomComponent>> {
+    let modules = match fs::read_to_string(project_path.join("go.sum")) {
+        Ok(go_sum) => go_sum_modules(&go_sum),
+        Err(_) => {
+            let go_mod =
+                fs::read_to_string(project_path.join("go.mod")).context("Failed to read go.mod")?;
+            go_requirements(&go_mod)
+                .map(|(path, version)| (path.to_string(), version.to_string()))
+                .collect()
+        }
+    };
+    Ok(modules
+        .into_iter()
+        .map(|(path, version)| SbomComponent {
+            purl: format!("pkg:golang/{path}@{version}"),
+            name: path,
+            version,
+            // go.sum records hashes of the module's file tree rather than a checksum of an archive
+            sha256: None,
+        })
+        .collect())
+}
+
+/// Returns the modules and versions listed in a `go.sum` file, leaving out the entries that only cover a
+/// module's `go.mod` file
+fn go_sum_modules(go_sum: &str) -> BTreeSet<(String, String)> {
+    go_sum
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?, parts.next()?))
+        })
+        .filter(|(_, version)| !version.ends_with("/go.mod"))
+        .map(|(path, version)| (path.to_string(), version.to_string()))
+        .collect()
+}
+
+/// Returns the project type, for describing the artifact
+fn artifact_kind(config: &ProjectConfig) -> &'static str {
+    match config.project_type {
+        TypeConfig::Actor(_) => "actor",
+        TypeConfig::Provider(_) => "provider",
+        TypeConfig::Interface(_) => "interface",
+    }
+}
+
+/// Returns a UUID derived from the artifact's digest and the time the SBOM was generated, so every generated
+/// SBOM has a unique serial number
+fn document_uuid(artifact: &BuildArtifact, timestamp: &str) -> String {
+    let hash = format!(
+        "{:x}",
+        Sha256::digest(format!("{}{timestamp}", artifact.digest))
+    );
+    format!(
+        "{}-{}-4{}-a{}-{}",
+        &hash[0..8],
+        &hash[8..12],
+        &hash[13..16],
+        &hash[17..20],
+        &hash[20..32]
+    )
+}
+
+fn cyclonedx_document(
+    config: &ProjectConfig,
+    artifact: &BuildArtifact,
+    components: &[SbomComponent],
+    timestamp: &str,
+) -> Value {
+    let mut properties = vec![json!({ "name": "wasmcloud:type", "value": artifact_kind(config) })];
+    if let Some(keys) = &artifact.signing_keys {
+        properties.push(json!({ "name": SUBJECT_PROPERTY, "value": keys.subject }));
+        properties.push(json!({ "name": ISSUER_PROPERTY, "value": keys.issuer }));
+    }
+    let mut component = json!({
+        "type": "application",
+        "bom-ref": config.common.name,
+        "name": config.common.name,
+        "version": config.common.version.to_string(),
+        "properties": properties,
+    });
+    if let Some(hex) = artifact.digest.strip_prefix("sha256:") {
+        component["hashes"] = json!([{ "alg": "SHA-256", "content": hex }]);
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.4",
+        "serialNumber": format!("urn:uuid:{}", document_uuid(artifact, timestamp)),
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": [{ "vendor": "wasmCloud", "name": "wash", "version": env!("CARGO_PKG_VERSION") }],
+            "component": component,
+        },
+        "components": components.iter().map(|c| {
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": c.purl,
+                "name": c.name,
+                "version": c.version,
+                "purl": c.purl,
+            });
+            if let Some(sha256) = &c.sha256 {
+                component["hashes"] = json!([{ "alg": "SHA-256", "content": sha256 }]);
+            }
+            component
+        }).collect::<Vec<_>>(),
+        "dependencies": [{
+            "ref": config.common.name,
+            "dependsOn": components.iter().map(|c| c.purl.as_str()).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+fn spdx_document(
+    config: &ProjectConfig,
+    artifact: &BuildArtifact,
+    components: &[SbomComponent],
+    timestamp: &str,
+) -> Value {
+    // SPDX identifiers may only contain letters, numbers, `.` and `-`
+    let spdx_id = |name: &str| -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect()
+    };
+    let artifact_id = format!("SPDXRef-{}", spdx_id(&config.common.name));
+
+    let mut external_refs = Vec::new();
+    if let Some(keys) = &artifact.signing_keys {
+        external_refs.push(json!({
+            "referenceCategory": "OTHER",
+            "referenceType": SUBJECT_PROPERTY,
+            "referenceLocator": keys.subject,
+        }));
+        external_refs.push(json!({
+            "referenceCategory": "OTHER",
+            "referenceType": ISSUER_PROPERTY,
+            "referenceLocator": keys.issuer,
+        }));
+    }
+    let mut artifact_package = json!({
+        "SPDXID": artifact_id,
+        "name": config.common.name,
+        "versionInfo": config.common.version.to_string(),
+        "packageFileName": artifact.path.file_name().map(|name| name.to_string_lossy()),
+        "primaryPackagePurpose": "APPLICATION",
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "comment": format!("wasmCloud {}", artifact_kind(config)),
+        "externalRefs": external_refs,
+    });
+    if let Some(hex) = artifact.digest.strip_prefix("sha256:") {
+        artifact_package["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": hex }]);
+    }
+
+    let mut packages = vec![artifact_package];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": artifact_id,
+    })];
+    for (index, c) in components.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{index}-{}", spdx_id(&c.name));
+        let mut package = json!({
+            "SPDXID": id,
+            "name": c.name,
+            "versionInfo": c.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": c.purl,
+            }],
+        });
+        if let Some(sha256) = &c.sha256 {
+            package["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": sha256 }]);
+        }
+        packages.push(package);
+        relationships.push(json!({
+            "spdxElementId": artifact_id,
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}-{}", config.common.name, config.common.version),
+        "documentNamespace": format!(
+            "https://wasmcloud.com/spdx/{}-{}-{}",
+            config.common.name,
+            config.common.version,
+            document_uuid(artifact, timestamp)
+        ),
+        "creationInfo": {
+            "created": timestamp,
+            "creators": [format!("Tool: wash-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use semver::Version;
+
+    use super::*;
+    use crate::build::SigningKeys;
+    use crate::parser::{
+        ActorConfig, CommonConfig, DevConfig, HooksConfig, RustConfig, TinyGoConfig, TypeConfig,
+    };
+
+    const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "echo"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "wasmbus-rpc",
+]
+
+[[package]]
+name = "other-member"
+version = "0.1.0"
+dependencies = [
+ "tokio",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.163"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "2113ab51b87a539ae008b5c6c02dc020ffa39afd2d83cffcb3f4eb2722cebec2"
+
+[[package]]
+name = "tokio"
+version = "1.28.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "94d7b1cfd2aa4011f2de74c2c4c63665e27a71006b0a192dcd2710272e73dfa2"
+
+[[package]]
+name = "wasmbus-rpc"
+version = "0.13.0"
+source = "git+https://github.com/wasmcloud/weld?branch=main#1234"
+dependencies = [
+ "serde 1.0.163",
+]
+"#;
+
+    fn test_config() -> ProjectConfig {
+        ProjectConfig {
+            language: LanguageConfig::Rust(RustConfig::default()),
+            project_type: TypeConfig::Actor(ActorConfig::default()),
+            common: CommonConfig {
+                name: "echo".to_string(),
+                version: Version::parse("0.1.0").unwrap(),
+                path: PathBuf::from("/tmp/echo"),
+                wasm_bin_name: None,
+                hooks: HooksConfig::default(),
+                dev: DevConfig::default(),
+            },
+        }
+    }
+
+    fn test_artifact() -> BuildArtifact {
+        BuildArtifact {
+            path: PathBuf::from("/tmp/echo/build/echo_s.wasm"),
+            digest: "sha256:93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+                .to_string(),
+            size: 8,
+            signing_keys: Some(SigningKeys {
+                issuer: "AISSUER".to_string(),
+                subject: "MSUBJECT".to_string(),
+            }),
+            duration: Duration::from_secs(1),
+            size_report: None,
+        }
+    }
+
+    /// Components of the packages in [LOCKFILE] that the echo package depends on
+    fn locked_components() -> Vec<SbomComponent> {
+        let checksums = locked_checksums(LOCKFILE).unwrap();
+        vec![
+            cargo_component(
+                "serde",
+                "1.0.163",
+                Some("registry+https://github.com/rust-lang/crates.io-index"),
+                checksums
+                    .get(&("serde".to_string(), "1.0.163".to_string()))
+                    .cloned(),
+            ),
+            cargo_component(
+                "wasmbus-rpc",
+                "0.13.0",
+                Some("git+https://github.com/wasmcloud/weld?branch=main#1234"),
+                checksums
+                    .get(&("wasmbus-rpc".to_string(), "0.13.0".to_string()))
+                    .cloned(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn can_describe_cargo_packages() {
+        assert_eq!(
+            locked_components(),
+            vec![
+                SbomComponent {
+                    name: "serde".to_string(),
+                    version: "1.0.163".to_string(),
+                    purl: "pkg:cargo/serde@1.0.163".to_string(),
+                    sha256: Some(
+                        "2113ab51b87a539ae008b5c6c02dc020ffa39afd2d83cffcb3f4eb2722cebec2"
+                            .to_string()
+                    ),
+                },
+                SbomComponent {
+                    name: "wasmbus-rpc".to_string(),
+                    version: "0.13.0".to_string(),
+                    purl: "pkg:cargo/wasmbus-rpc@0.13.0?vcs_url=git%2Bhttps%3A//github.com/wasmcloud/weld%401234"
+                        .to_string(),
+                    sha256: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_collects_normal_dependencies_for_target() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["normal", "dev", "build", "windows"] {
+            fs::create_dir_all(dir.path().join(name).join("src")).unwrap();
+            fs::write(
+                dir.path().join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+            )
+            .unwrap();
+            fs::write(dir.path().join(name).join("src/lib.rs"), "").unwrap();
+        }
+        let echo = dir.path().join("echo");
+        fs::create_dir_all(echo.join("src")).unwrap();
+        fs::write(echo.join("src/lib.rs"), "").unwrap();
+        fs::write(
+            echo.join("Cargo.toml"),
+            r#"[package]
+name = "echo"
+version = "0.1.0"
+
+[dependencies]
+normal = { path = "../normal" }
+
+[dev-dependencies]
+dev = { path = "../dev" }
+
+[build-dependencies]
+build = { path = "../build" }
+
+[target.'cfg(windows)'.dependencies]
+windows = { path = "../windows" }
+"#,
+        )
+        .unwrap();
+
+        let components = rust_components(&echo, &["wasm32-unknown-unknown".to_string()]).unwrap();
+        assert_eq!(
+            components,
+            vec![SbomComponent {
+                name: "normal".to_string(),
+                version: "0.1.0".to_string(),
+                purl: "pkg:cargo/normal@0.1.0".to_string(),
+                sha256: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn can_read_go_sum_modules() {
+        let go_sum = "github.com/wasmcloud/actor-tinygo v0.1.4 h1:abc=\n\
+            github.com/wasmcloud/actor-tinygo v0.1.4/go.mod h1:def=\n\
+            github.com/wasmcloud/tinygo-msgpack v0.1.4/go.mod h1:ghi=\n";
+        assert_eq!(
+            go_sum_modules(go_sum).into_iter().collect::<Vec<_>>(),
+            vec![(
+                "github.com/wasmcloud/actor-tinygo".to_string(),
+                "v0.1.4".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn documents_record_artifact_digest_and_subject() {
+        let config = test_config();
+        let artifact = test_artifact();
+        let components = locked_components();
+        let timestamp = "2023-06-01T00:00:00Z";
+
+        let cyclonedx = cyclonedx_document(&config, &artifact, &components, timestamp);
+        let component = &cyclonedx["metadata"]["component"];
+        assert_eq!(
+            component["hashes"][0]["content"],
+            "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert!(component["properties"]
+            .as_array()
+            .unwrap()
+            .contains(&json!({ "name": SUBJECT_PROPERTY, "value": "MSUBJECT" })));
+        assert_eq!(cyclonedx["components"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            cyclonedx["components"][0]["purl"],
+            "pkg:cargo/serde@1.0.163"
+        );
+
+        let spdx = spdx_document(&config, &artifact, &components, timestamp);
+        let package = &spdx["packages"][0];
+        assert_eq!(package["SPDXID"], "SPDXRef-echo");
+        assert_eq!(
+            package["checksums"][0]["checksumValue"],
+            "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert_eq!(package["externalRefs"][0]["referenceLocator"], "MSUBJECT");
+        assert_eq!(
+            spdx["packages"][2]["SPDXID"],
+            "SPDXRef-Package-1-wasmbus-rpc"
+        );
+        assert_eq!(spdx["relationships"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn can_write_sbom_for_tinygo_project() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module github.com/example/echo\n\nrequire github.com/wasmcloud/actor-tinygo v0.1.4\n",
+        )
+        .unwrap();
+        let mut config = test_config();
+        config.language = LanguageConfig::TinyGo(TinyGoConfig::default());
+        config.common.path = dir.path().to_path_buf();
+        let mut artifact = test_artifact();
+        artifact.path = dir.path().join("echo_s.wasm");
+
+        let path = write_sbom(&config, &artifact, SbomFormat::CycloneDx)
+            .await
+            .unwrap();
+        assert_eq!(path, dir.path().join("echo_s.wasm.cdx.json"));
+        let sbom: Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(
+            sbom["components"][0]["purl"],
+            "pkg:golang/github.com/wasmcloud/actor-tinygo@v0.1.4"
+        );
+    }
+}
@@ -0,0 +1,564 @@
+//! Discover and build every wasmCloud project in a monorepo, respecting the dependencies between them
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+
+use super::{build_project, SignConfig};
+use crate::parser::{config_environments, get_config_for_environment, ProjectConfig};
+
+/// Name of the file, at the root of a monorepo, listing the directories of wasmCloud projects to build
+pub const WORKSPACE_FILE_NAME: &str = "wasmcloud-workspace.toml";
+
+/// Directories that are never searched for projects when walking a workspace
+const IGNORED_WORKSPACE_DIRS: [&str; 4] = ["target", "build", ".git", "node_modules"];
+
+#[derive(serde::Deserialize, Debug, Default)]
+struct RawWorkspaceConfig {
+    /// Project directories, relative to the workspace root. A trailing `/*` matches every directory inside of a folder
+    #[serde(default)]
+    members: Vec<String>,
+    /// Project directories, relative to the workspace root, to leave out even if matched by `members`
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// The outcome of building a single project in a workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildStatus {
+    /// The project was built successfully
+    Built,
+    /// Building the project failed
+    Failed,
+    /// The project was not built because one of its dependencies failed to build
+    Skipped,
+}
+
+/// Result of building a single project as part of a workspace build
+#[derive(Debug, Clone)]
+pub struct ProjectBuildResult {
+    /// Name of the project
+    pub name: String,
+    /// Path to the project directory
+    pub path: PathBuf,
+    /// Whether the project was built, failed to build, or skipped
+    pub status: BuildStatus,
+    /// Path to the built artifact, if the build succeeded
+    pub artifact: Option<PathBuf>,
+    /// Time spent building the project
+    pub duration: Duration,
+    /// The reason the project failed to build or was skipped
+    pub error: Option<String>,
+}
+
+/// Finds the directories of every wasmCloud project in the workspace rooted at `root`.
+///
+/// Projects are discovered, in order of preference, from the `members` listed in a `wasmcloud-workspace.toml`
+/// file, from the members of a Cargo workspace, or by searching `root` for `wasmcloud.toml` files. Only
+/// directories containing a `wasmcloud.toml` file are returned.
+pub fn discover_projects(root: &Path) -> Result<Vec<PathBuf>> {
+    let root = fs::canonicalize(root)
+        .with_context(|| format!("Workspace root {} does not exist", root.display()))?;
+
+    let workspace_file = root.join(WORKSPACE_FILE_NAME);
+    let mut dirs = if workspace_file.is_file() {
+        let raw: RawWorkspaceConfig = toml::from_str(&fs::read_to_string(&workspace_file)?)
+            .with_context(|| format!("Invalid workspace file {}", workspace_file.display()))?;
+        expand_members(&root, &raw.members, &raw.exclude, &workspace_file)?
+    } else {
+        match cargo_workspace_members(&root)? {
+            Some(members) => members,
+            None => find_project_dirs(&root),
+        }
+    };
+
+    dirs.retain(|dir| dir.join("wasmcloud.toml").is_file());
+    dirs.sort();
+    dirs.dedup();
+    Ok(dirs)
+}
+
+/// Builds every project in the workspace rooted at `root`, see [discover_projects] for how projects are found.
+///
+/// A project that depends on another project in the workspace (through a Cargo path dependency or a `go.mod`
+/// replace directive pointing into the other project's directory) is built after its dependency. Projects
+/// that do not depend on each other are built in parallel. When a project fails to build, or its `wasmcloud.toml`
+/// is invalid, every project that depends on it is skipped, while unrelated projects are still built.
+///
+/// # Arguments
+/// * `root`: Path to the root directory of the workspace
+/// * `signing`: Optional [SignConfig] used to sign each project artifact. If omitted, artifacts will only be built
+/// * `environment`: Optional name of the environment overlay to apply to the projects that define it
+pub async fn build_workspace(
+    root: &Path,
+    signing: Option<SignConfig>,
+    environment: Option<&str>,
+) -> Result<Vec<ProjectBuildResult>> {
+    let dirs = discover_projects(root)?;
+    if dirs.is_empty() {
+        return Err(anyhow!(
+            "No wasmCloud projects found in workspace {}",
+            root.display()
+        ));
+    }
+    // Invalid configurations are reported as failed projects, so they don't prevent the others from building
+    let configs = dirs
+        .iter()
+        .map(|dir| load_project_config(dir, environment))
+        .collect::<Vec<_>>();
+    let names = dirs
+        .iter()
+        .zip(&configs)
+        .map(|(dir, config)| match config {
+            Ok(config) => config.common.name.clone(),
+            Err(_) => dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| dir.display().to_string()),
+        })
+        .collect::<Vec<_>>();
+
+    let dependencies = project_dependencies(&dirs);
+    let levels = build_levels(&dependencies)?;
+    let mut results: Vec<Option<ProjectBuildResult>> = vec![None; dirs.len()];
+
+    for level in levels {
+        let mut handles = Vec::new();
+        for idx in level {
+            let failed_dependency = dependencies[idx].iter().find(|dep| {
+                results[**dep]
+                    .as_ref()
+                    .map(|r| r.status != BuildStatus::Built)
+                    .unwrap_or(true)
+            });
+            if let Some(dep) = failed_dependency {
+                results[idx] = Some(ProjectBuildResult {
+                    name: names[idx].clone(),
+                    path: dirs[idx].clone(),
+                    status: BuildStatus::Skipped,
+                    artifact: None,
+                    duration: Duration::ZERO,
+                    error: Some(format!("dependency {} was not built", names[*dep])),
+                });
+                continue;
+            }
+
+            let config = match &configs[idx] {
+                Ok(config) => config.clone(),
+                Err(e) => {
+                    results[idx] = Some(ProjectBuildResult {
+                        name: names[idx].clone(),
+                        path: dirs[idx].clone(),
+                        status: BuildStatus::Failed,
+                        artifact: None,
+                        duration: Duration::ZERO,
+                        error: Some(format!("invalid project configuration: {e:#}")),
+                    });
+                    continue;
+                }
+            };
+            let signing = signing.clone();
+            handles.push((
+                idx,
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    let result = build_project(&config, signing).await;
+                    (result, started.elapsed())
+                }),
+            ));
+        }
+
+        for (idx, handle) in handles {
+            let (result, duration) = handle
+                .await
+                .map_err(|e| anyhow!("Build task for project panicked: {e}"))?;
+            let (status, artifact, error) = match result {
+                Ok(artifact) => (BuildStatus::Built, Some(artifact.path), None),
+                Err(e) => (BuildStatus::Failed, None, Some(format!("{e:#}"))),
+            };
+            results[idx] = Some(ProjectBuildResult {
+                name: names[idx].clone(),
+                path: dirs[idx].clone(),
+                status,
+                artifact,
+                duration,
+                error,
+            });
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Loads the configuration of the project in `dir`, applying the environment overlay if the project defines it
+fn load_project_config(dir: &Path, environment: Option<&str>) -> Result<ProjectConfig> {
+    // Environments are usually only defined by the projects that deploy differently in them
+    let environment = match environment {
+        Some(name)
+            if config_environments(Some(dir.to_path_buf()))?
+                .iter()
+                .any(|e| e == name) =>
+        {
+            Some(name)
+        }
+        _ => None,
+    };
+    get_config_for_environment(Some(dir.to_path_buf()), Some(true), environment)
+}
+
+/// Expands the `members` of a workspace, minus the `exclude`d directories, into project directories. Both are
+/// glob patterns relative to `root`, e.g. `actors/*`. Every member pattern must match at least one directory,
+/// while exclusions may match none.
+fn expand_members(
+    root: &Path,
+    members: &[String],
+    exclude: &[String],
+    manifest: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut excluded = Vec::new();
+    for pattern in exclude {
+        excluded.extend(expand_pattern(root, pattern)?);
+    }
+
+    let mut dirs = Vec::new();
+    for pattern in members {
+        let matched = expand_pattern(root, pattern)?;
+        if matched.is_empty() {
+            bail!(
+                "Workspace member `{pattern}` in {} does not match any directory in {}. Create the project, or remove it from `members`",
+                manifest.display(),
+                root.display()
+            );
+        }
+        dirs.extend(matched.into_iter().filter(|dir| !excluded.contains(dir)));
+    }
+    Ok(dirs)
+}
+
+/// Returns the directories matching a glob pattern relative to `root`
+fn expand_pattern(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let root = root
+        .to_str()
+        .ok_or_else(|| anyhow!("Workspace root {} is not valid UTF-8", root.display()))?;
+    // The root is matched literally, even if its path contains glob characters
+    let full_pattern = format!("{}/{pattern}", glob::Pattern::escape(root));
+    let paths = glob::glob(&full_pattern)
+        .with_context(|| format!("Invalid workspace member pattern `{pattern}`"))?;
+
+    let mut dirs = Vec::new();
+    for path in paths {
+        let path = path?;
+        if path.is_dir() {
+            dirs.push(fs::canonicalize(path)?);
+        }
+    }
+    Ok(dirs)
+}
+
+/// Returns the member directories of the Cargo workspace at `root`, if there is one
+fn cargo_workspace_members(root: &Path) -> Result<Option<Vec<PathBuf>>> {
+    let manifest = match fs::read_to_string(root.join("Cargo.toml")) {
+        Ok(contents) => contents.parse::<toml::Value>()?,
+        Err(_) => return Ok(None),
+    };
+    let workspace = match manifest.get("workspace") {
+        Some(workspace) => workspace,
+        None => return Ok(None),
+    };
+
+    let patterns = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut members = expand_members(
+        root,
+        &patterns("members"),
+        &patterns("exclude"),
+        &root.join("Cargo.toml"),
+    )?;
+    // A root package can be a wasmCloud project alongside the workspace members
+    members.push(root.to_path_buf());
+    Ok(Some(members))
+}
+
+/// Searches `root` for directories containing a `wasmcloud.toml` file, respecting ignore files
+fn find_project_dirs(root: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(root)
+        .filter_entry(|entry| {
+            !IGNORED_WORKSPACE_DIRS
+                .iter()
+                .any(|dir| entry.file_name() == *dir)
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name() == "wasmcloud.toml")
+        .filter_map(|entry| entry.path().parent().map(Path::to_path_buf))
+        .collect()
+}
+
+/// Returns, for each project directory, the indices of the other projects it depends on
+fn project_dependencies(dirs: &[PathBuf]) -> Vec<Vec<usize>> {
+    dirs.iter()
+        .enumerate()
+        .map(|(idx, dir)| {
+            let mut deps: Vec<usize> = local_dependency_paths(dir)
+                .iter()
+                .filter_map(|path| dependency_project(dirs, idx, path))
+                .collect();
+            deps.sort_unstable();
+            deps.dedup();
+            deps
+        })
+        .collect()
+}
+
+/// Returns the index of the project a dependency of the project at `idx` belongs to: the project whose directory
+/// is the dependency path, or else the innermost project containing it. Projects in a directory enclosing the
+/// dependent project, like the root package of a Cargo workspace, only match when they are the dependency itself.
+fn dependency_project(dirs: &[PathBuf], idx: usize, path: &Path) -> Option<usize> {
+    dirs.iter()
+        .enumerate()
+        .filter(|(other_idx, other)| {
+            *other_idx != idx
+                && (path == other.as_path()
+                    || (path.starts_with(other) && !dirs[idx].starts_with(other)))
+        })
+        .max_by_key(|(_, other)| other.components().count())
+        .map(|(other_idx, _)| other_idx)
+}
+
+/// Returns the canonical paths of the local dependencies of the project in `project_dir`, from the path
+/// dependencies in its `Cargo.toml` and the replace directives in its `go.mod`
+pub(crate) fn local_dependency_paths(project_dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Ok(manifest) = fs::read_to_string(project_dir.join("Cargo.toml")) {
+        if let Ok(manifest) = manifest.parse::<toml::Value>() {
+            let mut tables = vec![&manifest];
+            if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+                tables.extend(targets.values());
+            }
+            for table in tables {
+                for key in ["dependencies", "build-dependencies"] {
+                    let deps = table.get(key).and_then(|d| d.as_table());
+                    paths.extend(
+                        deps.into_iter()
+                            .flat_map(|deps| deps.values())
+                            .filter_map(|dep| dep.get("path").and_then(|p| p.as_str()))
+                            .map(|path| project_dir.join(path)),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Ok(go_mod) = fs::read_to_string(project_dir.join("go.mod")) {
+        paths.extend(
+            go_mod
+                .lines()
+                .filter_map(|line| line.split_once("=>"))
+                .map(|(_, target)| target.split_whitespace().next().unwrap_or_default())
+                .filter(|target| target.starts_with('.') || target.starts_with('/'))
+                .map(|target| project_dir.join(target)),
+        );
+    }
+
+    paths
+        .into_iter()
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect()
+}
+
+/// Groups projects into levels that can be built in order, where every project only depends on projects in
+/// earlier levels. Returns an error if the dependencies between projects contain a cycle.
+fn build_levels(dependencies: &[Vec<usize>]) -> Result<Vec<Vec<usize>>> {
+    let mut remaining: HashMap<usize, HashSet<usize>> = dependencies
+        .iter()
+        .enumerate()
+        .map(|(idx, deps)| (idx, deps.iter().copied().collect()))
+        .collect();
+    let mut levels = Vec::new();
+
+    while !remaining.is_empty() {
+        let mut level: Vec<usize> = remaining
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(idx, _)| *idx)
+            .collect();
+        if level.is_empty() {
+            return Err(anyhow!(
+                "Workspace projects have cyclical dependencies on each other"
+            ));
+        }
+        level.sort_unstable();
+        for idx in level.iter() {
+            remaining.remove(idx);
+        }
+        for deps in remaining.values_mut() {
+            deps.retain(|dep| !level.contains(dep));
+        }
+        levels.push(level);
+    }
+
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_order_projects_by_dependencies() {
+        // 0 <- 1 <- 2, 3 independent
+        let levels = build_levels(&[vec![], vec![0], vec![1, 0], vec![]]).unwrap();
+        assert_eq!(levels, vec![vec![0, 3], vec![1], vec![2]]);
+
+        assert!(build_levels(&[vec![1], vec![0]]).is_err());
+    }
+
+    #[test]
+    fn can_discover_workspace_members() {
+        let root = tempfile::tempdir().expect("Unable to create tempdir");
+        for dir in ["actors/echo", "actors/old", "actors/no_config", "interface"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        for dir in ["actors/echo", "actors/old", "interface"] {
+            fs::write(root.path().join(dir).join("wasmcloud.toml"), "").unwrap();
+        }
+        fs::write(
+            root.path().join(WORKSPACE_FILE_NAME),
+            "members = [\"actors/*\", \"interface\"]\nexclude = [\"actors/old\"]\n",
+        )
+        .unwrap();
+
+        let root_path = fs::canonicalize(root.path()).unwrap();
+        assert_eq!(
+            discover_projects(root.path()).unwrap(),
+            vec![root_path.join("actors/echo"), root_path.join("interface")]
+        );
+
+        // Without a workspace file, every project in the directory tree is found
+        fs::remove_file(root.path().join(WORKSPACE_FILE_NAME)).unwrap();
+        assert_eq!(
+            discover_projects(root.path()).unwrap(),
+            vec![
+                root_path.join("actors/echo"),
+                root_path.join("actors/old"),
+                root_path.join("interface")
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_workspace_members_that_do_not_exist() {
+        let root = tempfile::tempdir().expect("Unable to create tempdir");
+        for dir in ["actors/echo", "providers/kv"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+            fs::write(root.path().join(dir).join("wasmcloud.toml"), "").unwrap();
+        }
+        fs::write(
+            root.path().join(WORKSPACE_FILE_NAME),
+            "members = [\"*/[ek]*\"]\nexclude = [\"missing\"]\n",
+        )
+        .unwrap();
+        let root_path = fs::canonicalize(root.path()).unwrap();
+        assert_eq!(
+            discover_projects(root.path()).unwrap(),
+            vec![
+                root_path.join("actors/echo"),
+                root_path.join("providers/kv")
+            ]
+        );
+
+        fs::write(
+            root.path().join(WORKSPACE_FILE_NAME),
+            "members = [\"actors/*\", \"interface\"]\n",
+        )
+        .unwrap();
+        let err = discover_projects(root.path()).unwrap_err().to_string();
+        assert!(
+            err.contains("Workspace member `interface`")
+                && err.contains("remove it from `members`"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn only_depends_on_projects_containing_dependencies() {
+        let root = tempfile::tempdir().expect("Unable to create tempdir");
+        let root_path = fs::canonicalize(root.path()).unwrap();
+        for dir in ["actor", "interface/rust", "shared"] {
+            fs::create_dir_all(root_path.join(dir)).unwrap();
+        }
+        fs::write(
+            root_path.join("actor/Cargo.toml"),
+            "[dependencies]\nshared = { path = \"../shared\" }\ninterface = { path = \"../interface/rust\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            root_path.join("Cargo.toml"),
+            "[dependencies]\nactor = { path = \"actor\" }\n",
+        )
+        .unwrap();
+
+        // The root project contains the shared crate, but the actor doesn't depend on it
+        let dirs = vec![
+            root_path.clone(),
+            root_path.join("actor"),
+            root_path.join("interface"),
+        ];
+        assert_eq!(project_dependencies(&dirs), vec![vec![1], vec![2], vec![]]);
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_project_configurations() {
+        let root = tempfile::tempdir().expect("Unable to create tempdir");
+        fs::create_dir_all(root.path().join("broken")).unwrap();
+        fs::write(root.path().join("broken/wasmcloud.toml"), "name = ").unwrap();
+
+        let results = build_workspace(root.path(), None, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "broken");
+        assert_eq!(results[0].status, BuildStatus::Failed);
+        assert!(results[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("invalid project configuration"));
+    }
+
+    #[test]
+    fn can_find_local_dependencies() {
+        let root = tempfile::tempdir().expect("Unable to create tempdir");
+        let root_path = fs::canonicalize(root.path()).unwrap();
+        fs::create_dir_all(root_path.join("interface/rust")).unwrap();
+        fs::create_dir_all(root_path.join("actor")).unwrap();
+        fs::write(
+            root_path.join("actor/Cargo.toml"),
+            "[package]\nname = \"actor\"\n\n[dependencies]\nserde = \"1\"\ninterface = { path = \"../interface/rust\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            local_dependency_paths(&root_path.join("actor")),
+            vec![root_path.join("interface/rust")]
+        );
+    }
+}
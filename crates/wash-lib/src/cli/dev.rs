@@ -1,68 +1,920 @@
-use anyhow::Result;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    fs,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
 use console::style;
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match, WalkBuilder,
+};
+use sha2::{Digest, Sha256};
+use wasmbus_rpc::{common::Message, core::WasmCloudEntity, rpc_client::RpcClient};
 use wasmcloud_control_interface::Client;
+use wasmcloud_test_util::testing::{TestResult, TestResults};
 
 use crate::{
-    actor::{start_actor, stop_actor, StartActorArgs},
-    build::{build_project, SignConfig},
+    actor::{start_actor, stop_actor, update_actor, StartActorArgs},
+    build::{build_project, SignConfig, BUILD_OUTPUT, IGNORED_PROJECT_DIRS},
+    common::boxed_err_to_anyhow,
     context::default_timeout_ms,
     generate::emoji,
     id::{ModuleId, ServerId},
-    parser::{ProjectConfig, TypeConfig},
+    parser::{DevConfig, DevProviderConfig, DevTestConfig, ProjectConfig, TypeConfig},
+    provider::{start_provider, stop_provider, StartProviderArgs},
+    wait::ProviderStartedInfo,
 };
 
-/// Perform a single execution of the dev loop for an artifact
+tokio::task_local! {
+    /// Prefix of the lines logged by the dev loop, naming the project they are about when several projects are
+    /// developed at once. Set it for a task with `LOG_PREFIX.scope`.
+    pub static LOG_PREFIX: String;
+}
+
+/// Prints a status line of the dev loop to stderr, prefixed with the log prefix of the current task. When
+/// [`BUILD_OUTPUT`] is set for the task, the line is sent there instead, along with the output of the builds.
+pub fn print_status(line: impl AsRef<str>) {
+    status_printer()(line.as_ref())
+}
+
+/// Returns a function printing status lines the way [`print_status`] does in the current task, for callbacks that
+/// run outside of it, like the ones of a file watcher
+pub fn status_printer() -> impl Fn(&str) + Send + 'static {
+    let prefix = LOG_PREFIX.try_with(Clone::clone).unwrap_or_default();
+    let output = BUILD_OUTPUT.try_with(Clone::clone).ok();
+    move |line| {
+        let line = format!("{prefix}{line}");
+        let sent = output
+            .as_ref()
+            .map(|output| output.send(line.clone()).is_ok())
+            .unwrap_or(false);
+        if !sent {
+            eprintln!("{line}");
+        }
+    }
+}
+
+/// What the dev loop restarts on the dev host after each build
+#[derive(Debug, Clone)]
+pub enum DevTarget {
+    /// An actor, which is restarted from its file reference
+    Actor {
+        actor_id: ModuleId,
+        actor_ref: String,
+    },
+    /// A capability provider, which replaces the provider running with the same link name and contract ID
+    Provider { link_name: String },
+}
+
+/// How the dev loop replaced the running artifact with the rebuilt one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadStrategy {
+    /// The running actor was updated in place through the control interface, without downtime
+    Update,
+    /// The running artifact was stopped and the rebuilt one started in its place
+    Restart,
+}
+
+impl Display for ReloadStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReloadStrategy::Update => write!(f, "live update"),
+            ReloadStrategy::Restart => write!(f, "restart"),
+        }
+    }
+}
+
+/// Perform a single execution of the dev loop for an artifact, returning how the running artifact was reloaded.
+/// Nothing is reloaded for interfaces. The actor ID of the target is updated when the rebuilt actor has a new one.
+/// Once the artifact is reloaded, the test actor of the dev environment is run, and its results are printed.
 pub async fn run_dev_loop(
     project_cfg: &ProjectConfig,
-    actor_id: ModuleId,
-    actor_ref: &str,
+    target: &mut DevTarget,
     host_id: ServerId,
     ctl_client: &Client,
     sign_cfg: Option<SignConfig>,
-) -> Result<()> {
-    let built_artifact_path = build_project(project_cfg, sign_cfg)?.canonicalize()?;
+    environment: &DevEnvironment,
+) -> Result<Option<ReloadStrategy>> {
+    let built_artifact_path = build_project(project_cfg, sign_cfg).await?.path;
+    let strategy = reload_artifact(
+        project_cfg,
+        &built_artifact_path,
+        target,
+        host_id,
+        ctl_client,
+    )
+    .await?;
+    environment.run_tests().await;
+    Ok(strategy)
+}
+
+/// Replace the artifact running on the dev host with the one built at `built_artifact_path`, returning how it was
+/// reloaded. This is the second half of [run_dev_loop], for callers that build the project themselves.
+pub async fn reload_artifact(
+    project_cfg: &ProjectConfig,
+    built_artifact_path: &Path,
+    target: &mut DevTarget,
+    host_id: ServerId,
+    ctl_client: &Client,
+) -> Result<Option<ReloadStrategy>> {
+    let built_artifact_path = built_artifact_path.canonicalize()?;
 
     // Restart the artifact so that changes can be observed
-    match project_cfg.project_type {
-        TypeConfig::Interface(_) | TypeConfig::Provider(_) => {
-            eprintln!(
+    match (&project_cfg.project_type, target) {
+        (TypeConfig::Provider(provider_config), DevTarget::Provider { link_name }) => {
+            let provider_ref = format!("file://{}", built_artifact_path.display());
+            restart_provider(
+                ctl_client,
+                &host_id,
+                &provider_ref,
+                link_name,
+                &provider_config.capability_id,
+            )
+            .await?;
+            Ok(Some(ReloadStrategy::Restart))
+        }
+        (
+            TypeConfig::Actor(_),
+            DevTarget::Actor {
+                actor_id,
+                actor_ref,
+            },
+        ) => {
+            let strategy = reload_actor(ctl_client, &host_id, actor_id, actor_ref).await?;
+            print_status(format!(
+                "{} {}",
+                emoji::GREEN_CHECK,
+                style(format!(
+                    "reloaded actor @ [{}] ({strategy})",
+                    built_artifact_path.display()
+                ))
+                .bold()
+            ));
+            Ok(Some(strategy))
+        }
+        _ => {
+            print_status(format!(
                 "{} {}",
                 emoji::WARN,
-                style("`wash build` interfaces and providers are not yet supported, skipping...")
-                    .bold(),
-            );
+                style("`wash dev` does not support interfaces, skipping...").bold()
+            ));
+            Ok(None)
+        }
+    }
+}
+
+/// Replace the running actor with the one at `actor_ref`, updating it in place and falling back to stopping and
+/// starting it when the host rejects the update or can't be asked to update it
+async fn reload_actor(
+    ctl_client: &Client,
+    host_id: &ServerId,
+    actor_id: &mut ModuleId,
+    actor_ref: &str,
+) -> Result<ReloadStrategy> {
+    let reason = match update_actor(ctl_client, host_id, actor_id, actor_ref, None).await {
+        Ok(ack) if ack.accepted => return Ok(ReloadStrategy::Update),
+        Ok(ack) => format!("live update was rejected ({})", ack.error),
+        Err(e) => {
+            log::debug!("Failed to update actor {actor_id} live: {e:#}");
+            format!("live update failed ({e})")
+        }
+    };
+
+    print_status(format!(
+        "{} {}",
+        emoji::RECYCLE,
+        style(format!("{reason}, restarting actor...")).bold()
+    ));
+    stop_actor(
+        ctl_client,
+        host_id,
+        actor_id,
+        1,
+        None,
+        default_timeout_ms(),
+        false,
+    )
+    .await?;
+    let started = start_actor(StartActorArgs {
+        ctl_client,
+        host_id,
+        actor_ref,
+        count: 1,
+        skip_wait: false,
+        timeout_ms: None,
+    })
+    .await?;
+    // The actor ID changes when the rebuilt actor is signed with a different key
+    if let Some(started_id) = started.actor_id {
+        *actor_id = started_id
+            .parse()
+            .map_err(|e| anyhow!("invalid actor ID [{started_id}]: {e}"))?;
+    }
+    Ok(ReloadStrategy::Restart)
+}
+
+/// Name of the file listing paths, in `.gitignore` syntax, whose changes don't trigger a rebuild in `wash dev`
+pub const WASHIGNORE_FILE: &str = ".washignore";
+
+/// Names of the ignore files respected by `wash dev`, with the last one taking precedence
+const IGNORE_FILES: [&str; 2] = [".gitignore", WASHIGNORE_FILE];
+
+/// Decides which changes to the files of a project trigger a rebuild in the dev loop. Changes are ignored when
+/// they are to build output directories, to editor swap and backup files, or to paths matched by a `.gitignore`
+/// or `.washignore` file in the project. Writing a file with the contents it already had, like code generators
+/// run by build hooks do, is not a change either.
+pub struct ChangeFilter {
+    project_path: PathBuf,
+    /// Ignore files of the project, with the ones in deeper directories last
+    ignores: Vec<Gitignore>,
+    /// Digests of the contents of the project's files, as they were last seen
+    digests: HashMap<PathBuf, Vec<u8>>,
+    /// Directories inside the project whose changes belong to other projects
+    excluded: Vec<PathBuf>,
+}
+
+impl ChangeFilter {
+    /// Creates a filter for the project at `project_path`, recording the current contents of its files
+    pub fn new(project_path: &Path) -> Result<Self> {
+        let mut filter = ChangeFilter {
+            project_path: project_path.canonicalize().with_context(|| {
+                format!(
+                    "failed to resolve project path [{}]",
+                    project_path.display()
+                )
+            })?,
+            ignores: Vec::new(),
+            digests: HashMap::new(),
+            excluded: Vec::new(),
+        };
+        filter.load_ignores();
+        for path in filter.project_files() {
+            if !filter.is_ignored(&path, false) {
+                if let Some(digest) = file_digest(&path) {
+                    filter.digests.insert(path, digest);
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    /// The canonical path of the project directory, which is the one to watch
+    pub fn project_path(&self) -> &Path {
+        &self.project_path
+    }
+
+    /// Ignores the changes to `path`, e.g. because it is the directory of another project nested in this one
+    pub fn exclude(&mut self, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.digests.retain(|file, _| !file.starts_with(&path));
+        self.excluded.push(path);
+    }
+
+    /// Returns whether the change reported for `path` should trigger a rebuild, recording the current contents of
+    /// the file. Removing a file that was seen before is a change.
+    pub fn is_change(&mut self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        if is_dir || self.is_ignored(path, is_dir) {
+            return false;
+        }
+        if path
+            .file_name()
+            .map_or(false, |name| IGNORE_FILES.iter().any(|file| name == *file))
+        {
+            self.load_ignores();
         }
-        TypeConfig::Actor(_) => {
-            eprintln!(
+
+        match file_digest(path) {
+            Some(digest) => self.digests.insert(path.to_path_buf(), digest.clone()) != Some(digest),
+            None => {
+                let before = self.digests.len();
+                self.digests.retain(|file, _| !file.starts_with(path));
+                self.digests.len() != before
+            }
+        }
+    }
+
+    /// Returns whether changes to `path` are ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = match path.strip_prefix(&self.project_path) {
+            Ok(relative) => relative,
+            // Paths outside of the project are never part of it
+            Err(_) => return true,
+        };
+        if self
+            .excluded
+            .iter()
+            .any(|excluded| path.starts_with(excluded))
+        {
+            return true;
+        }
+        if let Some(Component::Normal(top)) = relative.components().next() {
+            if IGNORED_PROJECT_DIRS.iter().any(|dir| top == *dir) {
+                return true;
+            }
+        }
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, is_editor_file)
+        {
+            return true;
+        }
+
+        for ignore in self.ignores.iter().rev() {
+            if !path.starts_with(ignore.path()) {
+                continue;
+            }
+            match ignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    /// Reads every ignore file in the project
+    fn load_ignores(&mut self) {
+        let mut ignores: Vec<Gitignore> = self
+            .project_files()
+            .into_iter()
+            .filter(|path| {
+                path.file_name()
+                    .map_or(false, |name| IGNORE_FILES.iter().any(|file| name == *file))
+            })
+            .filter_map(|path| {
+                let root = path.parent()?;
+                let mut builder = GitignoreBuilder::new(root);
+                builder.add(&path);
+                builder.build().ok()
+            })
+            .collect();
+        // The walk lists `.gitignore` before `.washignore`, which keeps that order within a directory
+        ignores.sort_by_key(|ignore| ignore.path().components().count());
+        self.ignores = ignores;
+    }
+
+    /// Lists the files of the project, skipping build output directories and ignored paths
+    fn project_files(&self) -> Vec<PathBuf> {
+        WalkBuilder::new(&self.project_path)
+            .hidden(false)
+            .require_git(false)
+            .add_custom_ignore_filename(WASHIGNORE_FILE)
+            .filter_entry(|entry| {
+                entry.depth() != 1
+                    || !IGNORED_PROJECT_DIRS
+                        .iter()
+                        .any(|dir| entry.file_name() == *dir)
+            })
+            .sort_by_file_path(|a, b| a.cmp(b))
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map_or(false, |t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+}
+
+/// Returns whether `name` is one of the swap, backup or temporary files that editors write next to the files
+/// being edited
+fn is_editor_file(name: &str) -> bool {
+    name.ends_with('~')
+        || name.starts_with(".#")
+        || (name.starts_with('#') && name.ends_with('#'))
+        || [".swp", ".swo", ".swx", ".tmp", "___jb_tmp___", "___jb_old___"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        // Written by vim to check whether a directory is writable
+        || name == "4913"
+}
+
+/// Returns the digest of the file's contents, or `None` if it can't be read
+fn file_digest(path: &Path) -> Option<Vec<u8>> {
+    fs::read(path)
+        .ok()
+        .map(|contents| Sha256::digest(contents).to_vec())
+}
+
+/// The providers and links that `wash dev` set up from the `[dev]` section of `wasmcloud.toml`, kept so they can
+/// be torn down when the dev loop stops
+#[derive(Debug, Clone, Default)]
+pub struct DevEnvironment {
+    /// The actor under development that the providers were linked to
+    actor_id: Option<ModuleId>,
+    /// Providers started by `wash dev`, with their IDs. Providers that were already running are left alone
+    started: Vec<(String, DevProviderConfig)>,
+    /// Providers that were linked to the actor
+    linked: Vec<DevProviderConfig>,
+    /// The test actor invoked after each reload
+    test: Option<DevTest>,
+}
+
+/// Key of the origin of the invocations of test actors. It is not a real public key
+const DEV_TEST_ORIGIN_KEY: &str = "__WASH__";
+
+/// A test actor from the `[dev.test]` section of `wasmcloud.toml`, and the client to invoke it with
+#[derive(Clone)]
+struct DevTest {
+    rpc_client: RpcClient,
+    lattice_prefix: String,
+    actor_id: String,
+    /// Whether `wash dev` started the test actor, and should stop it
+    started: bool,
+    config: DevTestConfig,
+}
+
+impl Debug for DevTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DevTest")
+            .field("lattice_prefix", &self.lattice_prefix)
+            .field("actor_id", &self.actor_id)
+            .field("started", &self.started)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DevTest {
+    /// Invoke the test actor and decode its response as test results
+    async fn run(&self) -> Result<TestResults> {
+        let response = self
+            .rpc_client
+            .send_timeout(
+                WasmCloudEntity::new_actor(DEV_TEST_ORIGIN_KEY)?,
+                WasmCloudEntity::new_actor(&self.actor_id)?,
+                &self.lattice_prefix,
+                Message {
+                    method: &self.config.operation,
+                    arg: wasmbus_rpc::common::serialize(&self.config.payload)?.into(),
+                },
+                Duration::from_millis(self.config.timeout_ms),
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to invoke {} on test actor [{}]",
+                    self.config.operation, self.actor_id
+                )
+            })?;
+        wasmbus_rpc::common::deserialize::<TestResults>(&response).with_context(|| {
+            format!(
+                "response of the test actor is not test results: {}",
+                String::from_utf8_lossy(&response)
+            )
+        })
+    }
+}
+
+/// Ensure the providers in `dev_config` are running on the dev host, starting the ones that are not, and link
+/// each of them to the actor under development when there is one. When the config has a test actor and an RPC
+/// client is given, the test actor is started too, unless it is the actor under development or already running.
+pub async fn start_dev_environment(
+    ctl_client: &Client,
+    host_id: &ServerId,
+    dev_config: &DevConfig,
+    actor_id: Option<&ModuleId>,
+    rpc_client: Option<RpcClient>,
+) -> Result<DevEnvironment> {
+    let mut environment = DevEnvironment {
+        actor_id: actor_id.cloned(),
+        ..Default::default()
+    };
+    if let (Some(test), Some(rpc_client)) = (&dev_config.test, rpc_client) {
+        environment.test =
+            Some(start_dev_test(ctl_client, host_id, test, actor_id, rpc_client).await?);
+    }
+    if dev_config.providers.is_empty() {
+        return Ok(environment);
+    }
+
+    let inventory = ctl_client
+        .get_host_inventory(host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("failed to retrieve host inventory for host [{host_id}]"))?;
+
+    for provider in &dev_config.providers {
+        let running = inventory
+            .providers
+            .iter()
+            .find(|p| p.link_name == provider.link_name && p.contract_id == provider.contract_id);
+        let provider_id = match running {
+            Some(running) => running.id.clone(),
+            None => {
+                print_status(format!(
+                    "{} {}",
+                    emoji::WRENCH,
+                    style(format!(
+                        "starting provider [{}] ({}, link name {})...",
+                        provider.image_ref, provider.contract_id, provider.link_name
+                    ))
+                    .bold()
+                ));
+                let started = start_provider(StartProviderArgs {
+                    ctl_client,
+                    host_id,
+                    provider_ref: &provider.image_ref,
+                    link_name: &provider.link_name,
+                    config_json: None,
+                    skip_wait: false,
+                    timeout_ms: None,
+                })
+                .await?;
+                environment
+                    .started
+                    .push((started.provider_id.clone(), provider.clone()));
+                started.provider_id
+            }
+        };
+
+        if let Some(actor_id) = actor_id {
+            let ack = ctl_client
+                .advertise_link(
+                    actor_id,
+                    &provider_id,
+                    &provider.contract_id,
+                    &provider.link_name,
+                    provider.link_values.clone().into_iter().collect(),
+                )
+                .await
+                .map_err(boxed_err_to_anyhow)
+                .with_context(|| {
+                    format!("failed to link actor [{actor_id}] to provider [{provider_id}]")
+                })?;
+            if !ack.accepted {
+                bail!(
+                    "failed to link actor [{actor_id}] to provider [{provider_id}]: {}",
+                    ack.error
+                );
+            }
+            environment.linked.push(provider.clone());
+            print_status(format!(
                 "{} {}",
-                emoji::RECYCLE,
+                emoji::GREEN_CHECK,
                 style(format!(
-                    "restarting actor @ [{}]...",
-                    built_artifact_path.display()
+                    "linked actor to provider [{}] ({}, link name {})",
+                    provider_id, provider.contract_id, provider.link_name
                 ))
-                .bold(),
-            );
-            stop_actor(
+                .bold()
+            ));
+        }
+    }
+
+    Ok(environment)
+}
+
+/// Find the test actor on the dev host, starting it when it is given as a reference and isn't running yet
+async fn start_dev_test(
+    ctl_client: &Client,
+    host_id: &ServerId,
+    test: &DevTestConfig,
+    dev_actor_id: Option<&ModuleId>,
+    rpc_client: RpcClient,
+) -> Result<DevTest> {
+    let (actor_id, started) = match &test.actor {
+        None => (
+            dev_actor_id
+                .context("[dev.test] needs a test actor when developing a provider")?
+                .to_string(),
+            false,
+        ),
+        Some(actor_id) if ModuleId::from_str(actor_id).is_ok() => (actor_id.clone(), false),
+        Some(actor_ref) => {
+            let inventory = ctl_client
+                .get_host_inventory(host_id)
+                .await
+                .map_err(boxed_err_to_anyhow)
+                .with_context(|| {
+                    format!("failed to retrieve host inventory for host [{host_id}]")
+                })?;
+            match inventory
+                .actors
+                .into_iter()
+                .find(|actor| actor.image_ref.as_deref() == Some(actor_ref))
+            {
+                Some(running) => (running.id, false),
+                None => {
+                    print_status(format!(
+                        "{} {}",
+                        emoji::WRENCH,
+                        style(format!("starting test actor [{actor_ref}]...")).bold()
+                    ));
+                    let actor_id = start_actor(StartActorArgs {
+                        ctl_client,
+                        host_id,
+                        actor_ref,
+                        count: 1,
+                        skip_wait: false,
+                        timeout_ms: None,
+                    })
+                    .await?
+                    .actor_id
+                    .with_context(|| format!("failed to start test actor [{actor_ref}]"))?;
+                    (actor_id, true)
+                }
+            }
+        }
+    };
+
+    Ok(DevTest {
+        rpc_client,
+        lattice_prefix: ctl_client.lattice_prefix.clone(),
+        actor_id,
+        started,
+        config: test.clone(),
+    })
+}
+
+/// Print the results of a test actor, one line for each test and a summary
+fn report_test_results(results: &[TestResult]) {
+    for test in results {
+        if test.passed {
+            print_status(format!("{}: {}", style("Pass").green(), test.name));
+        } else {
+            // Failed tests may explain themselves with an error in their JSON data
+            let error = test
+                .snap_data
+                .as_ref()
+                .and_then(|data| serde_json::from_slice::<serde_json::Value>(data).ok())
+                .and_then(|data| data.get("error")?.as_str().map(|e| format!(": {e}")))
+                .unwrap_or_default();
+            print_status(format!("{}: {}{error}", style("Fail").red(), test.name));
+        }
+    }
+
+    let passed = results.iter().filter(|test| test.passed).count();
+    let summary = format!("test results: {passed}/{} passed", results.len());
+    let summary = if passed == results.len() {
+        style(summary).green()
+    } else {
+        style(summary).red()
+    };
+    print_status(format!("{}{}", emoji::TEST_TUBE, summary.bold()));
+}
+
+impl DevEnvironment {
+    /// Invoke the test actor from the `[dev.test]` section of `wasmcloud.toml`, if there is one, and print its
+    /// results. Failures are reported rather than returned, so the dev loop keeps running.
+    pub async fn run_tests(&self) {
+        let test = match &self.test {
+            Some(test) => test,
+            None => return,
+        };
+        print_status(format!(
+            "{}{}",
+            emoji::TEST_TUBE,
+            style(format!("running tests of actor [{}]...", test.actor_id)).bold()
+        ));
+        match test.run().await {
+            Ok(results) => report_test_results(&results),
+            Err(e) => print_status(format!(
+                "{}{}",
+                emoji::ERROR,
+                style(format!("failed to run tests: {e:#}")).bold()
+            )),
+        }
+    }
+
+    /// Remove the links and stop the providers and test actor that were set up by [start_dev_environment].
+    /// Failures are reported and skipped, so as much as possible is torn down.
+    pub async fn teardown(self, ctl_client: &Client, host_id: &ServerId) {
+        if let Some(actor_id) = &self.actor_id {
+            for provider in &self.linked {
+                if let Err(e) = ctl_client
+                    .remove_link(actor_id, &provider.contract_id, &provider.link_name)
+                    .await
+                {
+                    print_status(format!(
+                        "{} {}",
+                        emoji::WARN,
+                        style(format!(
+                            "failed to remove link to {} ({}): {e}",
+                            provider.contract_id, provider.link_name
+                        ))
+                        .bold()
+                    ));
+                }
+            }
+        }
+
+        for (provider_id, provider) in &self.started {
+            if let Err(e) = stop_provider(
                 ctl_client,
-                &host_id,
-                &actor_id,
-                1,
+                host_id,
+                provider_id,
+                &provider.link_name,
+                &provider.contract_id,
                 None,
                 default_timeout_ms(),
                 false,
             )
-            .await?;
-            start_actor(StartActorArgs {
+            .await
+            {
+                print_status(format!(
+                    "{} {}",
+                    emoji::WARN,
+                    style(format!("failed to stop provider [{provider_id}]: {e}")).bold()
+                ));
+            }
+        }
+
+        if let Some(test) = self.test.filter(|test| test.started) {
+            if let Err(e) = stop_actor(
                 ctl_client,
-                host_id: &host_id,
-                actor_ref,
-                count: 1,
-                skip_wait: false,
-                timeout_ms: None,
+                host_id,
+                &test.actor_id,
+                1,
+                None,
+                default_timeout_ms(),
+                false,
+            )
+            .await
+            {
+                print_status(format!(
+                    "{} {}",
+                    emoji::WARN,
+                    style(format!(
+                        "failed to stop test actor [{}]: {e}",
+                        test.actor_id
+                    ))
+                    .bold()
+                ));
+            }
+        }
+    }
+
+    /// Whether `wash dev` set up any providers, links or test actor
+    pub fn is_empty(&self) -> bool {
+        self.started.is_empty()
+            && self.linked.is_empty()
+            && !self.test.as_ref().map_or(false, |test| test.started)
+    }
+}
+
+/// Start the provider at `provider_ref` on the dev host, replacing the provider already running there with the
+/// same link name and contract ID. The links of the replaced provider are re-established for the new one, whose
+/// ID changes when the archive is signed with a different key.
+pub async fn restart_provider(
+    ctl_client: &Client,
+    host_id: &ServerId,
+    provider_ref: &str,
+    link_name: &str,
+    contract_id: &str,
+) -> Result<ProviderStartedInfo> {
+    let inventory = ctl_client
+        .get_host_inventory(host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| format!("failed to retrieve host inventory for host [{host_id}]"))?;
+    let existing = inventory
+        .providers
+        .into_iter()
+        .find(|p| p.link_name == link_name && p.contract_id == contract_id);
+
+    let mut links = Vec::new();
+    if let Some(existing) = existing {
+        links = ctl_client
+            .query_links()
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .context("failed to query links")?
+            .links
+            .into_iter()
+            .filter(|l| {
+                l.provider_id == existing.id
+                    && l.link_name == link_name
+                    && l.contract_id == contract_id
             })
-            .await?;
+            .collect();
+
+        print_status(format!(
+            "{} {}",
+            emoji::RECYCLE,
+            style(format!(
+                "stopping provider [{}] ({contract_id}, link name {link_name})...",
+                existing.id
+            ))
+            .bold()
+        ));
+        stop_provider(
+            ctl_client,
+            host_id,
+            &existing.id,
+            link_name,
+            contract_id,
+            None,
+            default_timeout_ms(),
+            false,
+        )
+        .await?;
+    }
+
+    print_status(format!(
+        "{} {}",
+        emoji::RECYCLE,
+        style(format!("starting provider @ [{provider_ref}]...")).bold()
+    ));
+    let started = start_provider(StartProviderArgs {
+        ctl_client,
+        host_id,
+        provider_ref,
+        link_name,
+        config_json: None,
+        skip_wait: false,
+        timeout_ms: None,
+    })
+    .await?;
+
+    for link in links {
+        let ack = ctl_client
+            .advertise_link(
+                &link.actor_id,
+                &started.provider_id,
+                contract_id,
+                link_name,
+                link.values,
+            )
+            .await
+            .map_err(boxed_err_to_anyhow)
+            .with_context(|| {
+                format!(
+                    "failed to re-establish link between actor [{}] and provider [{}]",
+                    link.actor_id, started.provider_id
+                )
+            })?;
+        if !ack.accepted {
+            bail!(
+                "failed to re-establish link between actor [{}] and provider [{}]: {}",
+                link.actor_id,
+                started.provider_id,
+                ack.error
+            );
         }
+        print_status(format!(
+            "{} {}",
+            emoji::GREEN_CHECK,
+            style(format!(
+                "re-established link from actor [{}]",
+                link.actor_id
+            ))
+            .bold()
+        ));
     }
 
-    Ok(())
+    Ok(started)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_filter_project_changes() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir");
+        let project = dir.path();
+        fs::create_dir_all(project.join("src/generated")).unwrap();
+        fs::create_dir_all(project.join("build")).unwrap();
+        fs::write(project.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(project.join(WASHIGNORE_FILE), "src/generated/\n").unwrap();
+        fs::write(project.join("src/.gitignore"), "!keep.log\n").unwrap();
+        fs::write(project.join("src/lib.rs"), "fn main() {}").unwrap();
+
+        let mut filter = ChangeFilter::new(project).unwrap();
+        let root = filter.project_path().to_path_buf();
+
+        assert!(filter.is_ignored(&root.join("build/actor_s.wasm"), false));
+        assert!(filter.is_ignored(&root.join("target/debug/actor.wasm"), false));
+        assert!(filter.is_ignored(&root.join("src/.lib.rs.swp"), false));
+        assert!(filter.is_ignored(&root.join("src/lib.rs~"), false));
+        assert!(filter.is_ignored(&root.join("wash.log"), false));
+        assert!(filter.is_ignored(&root.join("src/generated/mod.rs"), false));
+        assert!(!filter.is_ignored(&root.join("src/keep.log"), false));
+        assert!(!filter.is_ignored(&root.join("src/lib.rs"), false));
+
+        // Writing a file with the same contents is not a change
+        let lib = root.join("src/lib.rs");
+        fs::write(&lib, "fn main() {}").unwrap();
+        assert!(!filter.is_change(&lib));
+        fs::write(&lib, "fn main() { println!(); }").unwrap();
+        assert!(filter.is_change(&lib));
+        assert!(!filter.is_change(&lib));
+
+        fs::remove_file(&lib).unwrap();
+        assert!(filter.is_change(&lib));
+        assert!(!filter.is_change(&root.join("src/never_seen.rs")));
+
+        // Changing an ignore file reloads the ignores
+        fs::write(root.join(WASHIGNORE_FILE), "").unwrap();
+        assert!(filter.is_change(&root.join(WASHIGNORE_FILE)));
+        assert!(!filter.is_ignored(&root.join("src/generated/mod.rs"), false));
+
+        filter.exclude(&root.join("src/generated"));
+        assert!(filter.is_ignored(&root.join("src/generated/mod.rs"), false));
+    }
 }
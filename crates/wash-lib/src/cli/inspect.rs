@@ -1,4 +1,5 @@
 use super::{cached_oci_file, CommandOutput, OutputKind};
+use crate::build::{Provenance, PROVENANCE_TAG_PREFIX};
 use crate::registry::{get_oci_artifact, OciPullOptions};
 use anyhow::{anyhow, Result};
 use clap::Parser;
@@ -133,14 +134,23 @@ pub fn render_actor_claims(claims: Claims<Actor>, validation: TokenValidation) -
         "Capabilities"
     };
 
-    let tags = if let Some(tags) = &claims.metadata.as_ref().unwrap().tags {
-        if tags.is_empty() {
-            "None".to_string()
-        } else {
-            tags.join(",")
-        }
-    } else {
+    let all_tags = claims
+        .metadata
+        .as_ref()
+        .unwrap()
+        .tags
+        .clone()
+        .unwrap_or_default();
+    let provenance = Provenance::from_tags(&all_tags);
+    // Provenance is rendered separately, so it's left out of the displayed tags
+    let tags: Vec<String> = all_tags
+        .into_iter()
+        .filter(|t| !t.starts_with(PROVENANCE_TAG_PREFIX))
+        .collect();
+    let tags = if tags.is_empty() {
         "None".to_string()
+    } else {
+        tags.join(",")
     };
 
     let friendly_caps: Vec<String> = if let Some(caps) = &claims.metadata.as_ref().unwrap().caps {
@@ -175,6 +185,9 @@ pub fn render_actor_claims(claims: Claims<Actor>, validation: TokenValidation) -
     map.insert("tags".to_string(), json!(tags));
     map.insert("call_alias".to_string(), json!(call_alias));
     map.insert("name".to_string(), json!(name));
+    if let Some(provenance) = provenance.as_ref() {
+        map.insert("provenance".to_string(), json!(provenance));
+    }
 
     let mut table = render_core(&claims, validation);
 
@@ -212,6 +225,35 @@ pub fn render_actor_claims(claims: Claims<Actor>, validation: TokenValidation) -
         Alignment::Left,
     )]));
 
+    if let Some(provenance) = provenance {
+        table.add_row(Row::new(vec![TableCell::new_with_alignment(
+            "Build Provenance",
+            2,
+            Alignment::Center,
+        )]));
+
+        let commit = match (provenance.git_commit, provenance.git_dirty) {
+            (Some(commit), Some(true)) => format!("{commit} (dirty)"),
+            (Some(commit), _) => commit,
+            (None, _) => "None".to_string(),
+        };
+        for (label, value) in [
+            ("Git Commit", Some(commit)),
+            ("Built At", provenance.build_time),
+            ("Toolchain", provenance.toolchain),
+            ("Builder", provenance.builder),
+        ] {
+            table.add_row(Row::new(vec![
+                TableCell::new(label),
+                TableCell::new_with_alignment(
+                    value.unwrap_or_else(|| "None".to_string()),
+                    1,
+                    Alignment::Right,
+                ),
+            ]));
+        }
+    }
+
     CommandOutput::new(table.render(), map)
 }
 
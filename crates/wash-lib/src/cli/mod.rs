@@ -336,7 +336,7 @@ pub fn labels_vec_to_hashmap(constraints: Vec<String>) -> Result<HashMap<String,
     Ok(hm)
 }
 
-fn determine_directory(directory: Option<PathBuf>) -> Result<PathBuf> {
+pub(crate) fn determine_directory(directory: Option<PathBuf>) -> Result<PathBuf> {
     if let Some(d) = directory {
         Ok(d)
     } else {
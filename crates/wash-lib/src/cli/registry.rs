@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug, Clone)]
@@ -82,6 +84,11 @@ pub struct RegistryPushCommand {
     #[clap(short = 'a', long = "annotation", name = "annotations")]
     pub annotations: Option<Vec<String>>,
 
+    /// Path to a CycloneDX or SPDX JSON SBOM (e.g. generated by `wash build --sbom`) to attach to the pushed artifact.
+    /// The SBOM is pushed to the same repository, tagged `sha256-<digest>.sbom` after the artifact's manifest digest
+    #[clap(long = "sbom")]
+    pub sbom: Option<PathBuf>,
+
     #[clap(flatten)]
     pub opts: AuthOpts,
 }
@@ -3,7 +3,6 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use tokio::time::Duration;
 
 use crate::{
     actor::{start_actor, ActorStartedInfo, StartActorArgs},
@@ -15,7 +14,8 @@ use crate::{
     },
     context::default_timeout_ms,
     id::ServerId,
-    wait::{wait_for_provider_start_event, FindEventOutcome, ProviderStartedInfo},
+    provider::{self, StartProviderArgs},
+    wait::ProviderStartedInfo,
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -228,89 +228,49 @@ pub async fn start_provider(cmd: StartProviderCommand) -> Result<CommandOutput>
         None
     };
 
-    let mut receiver = client
-        .events_receiver()
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .context("Failed to get lattice event channel")?;
-
-    let ack = client
-        .start_provider(
-            &host,
-            &cmd.provider_ref,
-            Some(cmd.link_name.clone()),
-            None,
-            config_json.clone(),
-        )
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .with_context(|| {
-            format!(
-                "Failed to start provider {} on host {:?} with link name {} and configuration {:?}",
-                &cmd.provider_ref, &host, &cmd.link_name, &config_json
-            )
-        })?;
-
-    if !ack.accepted {
-        bail!("Start provider ack not accepted: {}", ack.error);
-    }
+    let ProviderStartedInfo {
+        provider_id,
+        provider_ref,
+        host_id,
+        contract_id,
+        link_name,
+    } = provider::start_provider(StartProviderArgs {
+        ctl_client: &client,
+        host_id: &host,
+        provider_ref: &cmd.provider_ref,
+        link_name: &cmd.link_name,
+        config_json,
+        skip_wait: cmd.skip_wait,
+        timeout_ms: Some(timeout_ms),
+    })
+    .await?;
 
     if cmd.skip_wait {
-        let text = format!("Start provider request received: {}", &cmd.provider_ref);
+        let text = format!("Start provider request received: {}", &provider_ref);
         return Ok(CommandOutput::new(
             text.clone(),
             HashMap::from([
                 ("result".into(), text.into()),
-                ("provider_ref".into(), cmd.provider_ref.into()),
-                ("link_name".into(), cmd.link_name.into()),
-                ("host_id".into(), host.to_string().into()),
+                ("provider_ref".into(), provider_ref.into()),
+                ("link_name".into(), link_name.into()),
+                ("host_id".into(), host_id.into()),
             ]),
         ));
     }
 
-    let event = wait_for_provider_start_event(
-        &mut receiver,
-        Duration::from_millis(timeout_ms),
-        host.to_string(),
-        cmd.provider_ref.clone(),
-    )
-    .await
-    .with_context(|| {
-        format!(
-            "Timed out waiting for start event for provider {} on host {}",
-            &cmd.provider_ref, &host
-        )
-    })?;
-
-    match event {
-        FindEventOutcome::Success(ProviderStartedInfo {
-            provider_id,
-            provider_ref,
-            host_id,
-            contract_id,
-            link_name,
-        }) => {
-            let text = format!(
-                "Provider [{}] (ref: [{}]) started on host [{}]",
-                &provider_id, &provider_ref, &host_id
-            );
-            Ok(CommandOutput::new(
-                text.clone(),
-                HashMap::from([
-                    ("result".into(), text.into()),
-                    ("provider_ref".into(), provider_ref.into()),
-                    ("provider_id".into(), provider_id.into()),
-                    ("link_name".into(), link_name.into()),
-                    ("contract_id".into(), contract_id.into()),
-                    ("host_id".into(), host_id.into()),
-                ]),
-            ))
-        }
-        FindEventOutcome::Failure(err) => Err(err).with_context(|| {
-            format!(
-                "Failed starting provider {} on host {}",
-                &cmd.provider_ref, &host
-            )
-        }),
-    }
+    let text = format!(
+        "Provider [{}] (ref: [{}]) started on host [{}]",
+        &provider_id, &provider_ref, &host_id
+    );
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("provider_ref".into(), provider_ref.into()),
+            ("provider_id".into(), provider_id.into()),
+            ("link_name".into(), link_name.into()),
+            ("contract_id".into(), contract_id.into()),
+            ("host_id".into(), host_id.into()),
+        ]),
+    ))
 }
@@ -1,7 +1,6 @@
 use anyhow::{bail, Result};
 use clap::Parser;
 use std::collections::HashMap;
-use tokio::time::Duration;
 
 use crate::{
     actor::stop_actor,
@@ -10,7 +9,8 @@ use crate::{
     config::WashConnectionOptions,
     context::default_timeout_ms,
     id::{validate_contract_id, ModuleId, ServerId, ServiceId},
-    wait::{wait_for_provider_stop_event, ActorStoppedInfo, FindEventOutcome, ProviderStoppedInfo},
+    provider,
+    wait::{ActorStoppedInfo, ProviderStoppedInfo},
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -101,68 +101,38 @@ pub async fn stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
-    let mut receiver = client
-        .events_receiver()
-        .await
-        .map_err(boxed_err_to_anyhow)?;
-
-    let ack = client
-        .stop_provider(
-            &cmd.host_id,
-            &cmd.provider_id,
-            &cmd.link_name,
-            &cmd.contract_id,
-            None,
-        )
-        .await
-        .map_err(boxed_err_to_anyhow)?;
-
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
-    }
-    if cmd.skip_wait {
-        let text = format!("Provider {} stop request received", cmd.provider_id);
-        return Ok(CommandOutput::new(
-            text.clone(),
-            HashMap::from([
-                ("result".into(), text.into()),
-                ("provider_id".into(), cmd.provider_id.to_string().into()),
-                ("link_name".into(), cmd.link_name.into()),
-                ("contract_id".into(), cmd.contract_id.into()),
-                ("host_id".into(), cmd.host_id.to_string().into()),
-            ]),
-        ));
-    }
-
-    let event = wait_for_provider_stop_event(
-        &mut receiver,
-        Duration::from_millis(timeout_ms),
-        cmd.host_id.to_string(),
-        cmd.provider_id.to_string(),
+    let ProviderStoppedInfo {
+        host_id,
+        provider_id,
+        link_name,
+        contract_id,
+    } = provider::stop_provider(
+        &client,
+        &cmd.host_id,
+        &cmd.provider_id,
+        &cmd.link_name,
+        &cmd.contract_id,
+        None,
+        timeout_ms,
+        cmd.skip_wait,
     )
     .await?;
 
-    match event {
-        FindEventOutcome::Success(ProviderStoppedInfo {
-            host_id,
-            provider_id,
-            link_name,
-            contract_id,
-        }) => {
-            let text = format!("Provider [{}] stopped successfully", &provider_id);
-            Ok(CommandOutput::new(
-                text.clone(),
-                HashMap::from([
-                    ("result".into(), text.into()),
-                    ("provider_id".into(), provider_id.into()),
-                    ("host_id".into(), host_id.into()),
-                    ("link_name".into(), link_name.into()),
-                    ("contract_id".into(), contract_id.into()),
-                ]),
-            ))
-        }
-        FindEventOutcome::Failure(err) => bail!("{}", err),
-    }
+    let text = if cmd.skip_wait {
+        format!("Provider {} stop request received", provider_id)
+    } else {
+        format!("Provider [{}] stopped successfully", &provider_id)
+    };
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("provider_id".into(), provider_id.into()),
+            ("host_id".into(), host_id.into()),
+            ("link_name".into(), link_name.into()),
+            ("contract_id".into(), contract_id.into()),
+        ]),
+    ))
 }
 
 pub async fn handle_stop_actor(cmd: StopActorCommand) -> Result<CommandOutput> {
@@ -15,6 +15,7 @@ use crate::context::WashContext;
 const WASH_DIR: &str = ".wash";
 
 const DOWNLOADS_DIR: &str = "downloads";
+const BUILD_CACHE_DIR: &str = "build_cache";
 pub const DEFAULT_NATS_HOST: &str = "127.0.0.1";
 pub const DEFAULT_NATS_PORT: &str = "4222";
 pub const DEFAULT_LATTICE_PREFIX: &str = "default";
@@ -61,6 +62,11 @@ pub fn downloads_dir() -> IoResult<PathBuf> {
     cfg_dir().map(|p| p.join(DOWNLOADS_DIR))
 }
 
+/// The path to the cache directory for artifacts built by wash
+pub fn build_cache_dir() -> IoResult<PathBuf> {
+    cfg_dir().map(|p| p.join(BUILD_CACHE_DIR))
+}
+
 #[derive(Clone)]
 /// Connection options for a Wash instance
 pub struct WashConnectionOptions {
@@ -2,7 +2,7 @@
 
 use std::{env, fs, io::Result, path::PathBuf};
 
-use crate::config::{downloads_dir, model_cache_dir};
+use crate::config::{build_cache_dir, downloads_dir, model_cache_dir};
 
 /// A type that allows you to clean up (i.e. drain) a set of caches and folders used by wasmcloud
 #[derive(Debug, Clone)]
@@ -18,6 +18,8 @@ pub enum Drain {
     Smithy,
     /// Remove downloaded and generated files from launching wasmCloud hosts
     Downloads,
+    /// Remove cached artifacts from building projects with wash build
+    Build,
 }
 
 impl IntoIterator for &Drain {
@@ -31,11 +33,13 @@ impl IntoIterator for &Drain {
                 /* Oci    */ env::temp_dir().join("wasmcloud_ocicache"),
                 /* Smithy */ model_cache_dir().unwrap_or_default(),
                 /* Downloads */ downloads_dir().unwrap_or_default(),
+                /* Build */ build_cache_dir().unwrap_or_default(),
             ],
             Drain::Lib => vec![env::temp_dir().join("wasmcloudcache")],
             Drain::Oci => vec![env::temp_dir().join("wasmcloud_ocicache")],
             Drain::Smithy => vec![model_cache_dir().unwrap_or_default()],
             Drain::Downloads => vec![downloads_dir().unwrap_or_default()],
+            Drain::Build => vec![build_cache_dir().unwrap_or_default()],
         };
         paths.into_iter()
     }
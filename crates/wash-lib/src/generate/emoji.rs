@@ -18,3 +18,4 @@ pub static INFO_SQUARE: Emoji<'_, '_> = Emoji("ℹ️️  ", "");
 pub static HOURGLASS_DRAINING: Emoji<'_, '_> = Emoji("⏳ ", "");
 pub static HOURGLASS_FULL: Emoji<'_, '_> = Emoji("⌛ ", "");
 pub static CONSTRUCTION_BARRIER: Emoji<'_, '_> = Emoji("🚧 ", "");
+pub static TEST_TUBE: Emoji<'_, '_> = Emoji("🧪 ", "");
@@ -0,0 +1,430 @@
+//! Infer the `wasmcloud.toml` config of an existing Cargo or TinyGo project
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use cargo_metadata::{DependencyKind, MetadataCommand, Package};
+use semver::Version;
+
+use super::{
+    ActorConfig, CommonConfig, DevConfig, HooksConfig, InterfaceConfig, LanguageConfig,
+    ProjectConfig, ProviderConfig, RustConfig, TinyGoConfig, TypeConfig,
+};
+
+/// Prefix of the names of the Rust interface crates published by wasmCloud, e.g. `wasmcloud-interface-httpserver`
+const RUST_INTERFACE_PREFIX: &str = "wasmcloud-interface-";
+/// Prefix of the module paths of the TinyGo interfaces published by wasmCloud, e.g.
+/// `github.com/wasmcloud/interfaces/httpserver/tinygo`
+const TINYGO_INTERFACE_PREFIX: &str = "github.com/wasmcloud/interfaces/";
+/// Interfaces whose capabilities are built into the wasmCloud host
+const BUILTIN_INTERFACES: [&str; 2] = ["logging", "numbergen"];
+/// Interfaces that don't correspond to a capability contract
+const NON_CAPABILITY_INTERFACES: [&str; 2] = ["factorial", "testing"];
+/// Version used for projects whose version can't be inferred, e.g. TinyGo projects
+const DEFAULT_VERSION: &str = "0.1.0";
+
+/// Options for [infer_config]
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// The type of project to generate config for, one of "actor", "provider" or "interface". Inferred from the
+    /// project when not set.
+    pub project_type: Option<String>,
+    /// The capability contract ID of a provider project. Inferred from the interface the provider depends on
+    /// when not set.
+    pub capability_id: Option<String>,
+}
+
+/// Inspects the Cargo (`Cargo.toml`) or TinyGo (`go.mod`) project in `project_path` and returns the config for
+/// its `wasmcloud.toml` file. The name, version, binary name and wasm target come from the project's manifest,
+/// and actor claims are inferred from its dependencies on wasmCloud interfaces.
+pub fn infer_config(project_path: &Path, options: &InitOptions) -> Result<ProjectConfig> {
+    let project_path = fs::canonicalize(project_path)
+        .with_context(|| format!("Path {} does not exist", project_path.display()))?;
+    if project_path.join("Cargo.toml").is_file() {
+        infer_rust_config(project_path, options)
+    } else if project_path.join("go.mod").is_file() {
+        infer_tinygo_config(project_path, options)
+    } else {
+        bail!(
+            "No Cargo.toml or go.mod file found in {}, only Rust and TinyGo projects are supported",
+            project_path.display()
+        )
+    }
+}
+
+fn infer_rust_config(project_path: PathBuf, options: &InitOptions) -> Result<ProjectConfig> {
+    let manifest_path = project_path.join("Cargo.toml");
+    let metadata = MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .no_deps()
+        .exec()
+        .context("Failed to read cargo metadata")?;
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| package.manifest_path == manifest_path)
+        .ok_or_else(|| anyhow!("Missing package information in {}", manifest_path.display()))?;
+
+    let interfaces: BTreeSet<String> = package
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.kind == DependencyKind::Normal)
+        .filter_map(|dependency| dependency.name.strip_prefix(RUST_INTERFACE_PREFIX))
+        .map(str::to_string)
+        .collect();
+
+    let project_type = match options.project_type.as_deref() {
+        Some(project_type) => project_type.to_string(),
+        None => guess_rust_project_type(&project_path, package).to_string(),
+    };
+    let type_config = match project_type.as_str() {
+        "actor" => TypeConfig::Actor(ActorConfig {
+            claims: capability_claims(&interfaces),
+            wasm_target: cargo_build_target(&project_path)
+                .filter(|target| target.starts_with("wasm32-"))
+                .unwrap_or_else(|| "wasm32-unknown-unknown".to_string()),
+            ..default_actor_config()
+        }),
+        "provider" => {
+            // The provider binary is named after the package unless it declares a differently named binary
+            let bin_name = package
+                .targets
+                .iter()
+                .find(|target| target.kind.iter().any(|kind| kind == "bin"))
+                .map(|target| target.name.clone())
+                .filter(|name| name != &package.name);
+            TypeConfig::Provider(ProviderConfig {
+                capability_id: provider_capability_id(options, &interfaces)?,
+                vendor: "NoVendor".to_string(),
+                targets: Vec::new(),
+                bin_name,
+            })
+        }
+        "interface" => TypeConfig::Interface(default_interface_config()),
+        other => bail!("Unknown project type: {other}"),
+    };
+
+    Ok(ProjectConfig {
+        language: LanguageConfig::Rust(RustConfig::default()),
+        project_type: type_config,
+        common: CommonConfig {
+            name: package.name.clone(),
+            version: Version::parse(&package.version.to_string())?,
+            // Cargo names the compiled library after the lib target, with dashes replaced by underscores
+            wasm_bin_name: package
+                .targets
+                .iter()
+                .find(|target| target.kind.iter().any(|kind| kind == "cdylib"))
+                .map(|target| target.name.replace('-', "_")),
+            path: project_path,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
+        },
+    })
+}
+
+/// Guesses the type of a rust project: actors are built as a `cdylib`, providers are binaries, and interfaces
+/// are generated from a `codegen.toml` file
+fn guess_rust_project_type(project_path: &Path, package: &Package) -> &'static str {
+    let has_kind = |expected: &str| {
+        package
+            .targets
+            .iter()
+            .any(|target| target.kind.iter().any(|kind| kind == expected))
+    };
+    if has_kind("cdylib") {
+        "actor"
+    } else if has_kind("bin") {
+        "provider"
+    } else if project_path.join("codegen.toml").is_file() {
+        "interface"
+    } else {
+        "actor"
+    }
+}
+
+/// Returns the `build.target` set in the `.cargo/config.toml` of the project or one of its parent directories
+fn cargo_build_target(project_path: &Path) -> Option<String> {
+    project_path.ancestors().find_map(|dir| {
+        ["config.toml", "config"].iter().find_map(|file| {
+            let config = fs::read_to_string(dir.join(".cargo").join(file)).ok()?;
+            let config = config.parse::<toml::Value>().ok()?;
+            config
+                .get("build")?
+                .get("target")?
+                .as_str()
+                .map(str::to_string)
+        })
+    })
+}
+
+fn infer_tinygo_config(project_path: PathBuf, options: &InitOptions) -> Result<ProjectConfig> {
+    let go_mod = fs::read_to_string(project_path.join("go.mod"))?;
+    let module = go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().trim_matches('"'))
+        .ok_or_else(|| anyhow!("Missing module directive in go.mod"))?;
+    let name = module.rsplit('/').next().unwrap_or(module).to_string();
+
+    let interfaces: BTreeSet<String> = go_requirements(&go_mod)
+        .filter_map(|(path, _)| path.strip_prefix(TINYGO_INTERFACE_PREFIX))
+        .filter_map(|path| path.split('/').next())
+        .map(str::to_string)
+        .collect();
+
+    let type_config = match options.project_type.as_deref().unwrap_or("actor") {
+        "actor" => TypeConfig::Actor(ActorConfig {
+            claims: capability_claims(&interfaces),
+            ..default_actor_config()
+        }),
+        "provider" | "interface" => {
+            bail!("Only actors are supported for TinyGo projects")
+        }
+        other => bail!("Unknown project type: {other}"),
+    };
+
+    Ok(ProjectConfig {
+        language: LanguageConfig::TinyGo(TinyGoConfig::default()),
+        project_type: type_config,
+        common: CommonConfig {
+            name,
+            version: Version::parse(DEFAULT_VERSION)?,
+            path: project_path,
+            wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
+        },
+    })
+}
+
+/// Returns the module paths and versions of the requirements in a `go.mod` file, from both single line
+/// `require` directives and `require ( ... )` blocks
+pub(crate) fn go_requirements(go_mod: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut in_block = false;
+    go_mod.lines().filter_map(move |line| {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                return None;
+            }
+            line
+        } else {
+            match line.strip_prefix("require") {
+                Some(rest) if rest.trim() == "(" => {
+                    in_block = true;
+                    return None;
+                }
+                Some(rest) if rest.starts_with(char::is_whitespace) => rest,
+                _ => return None,
+            }
+        };
+        let mut parts = requirement.split_whitespace();
+        Some((parts.next()?, parts.next().unwrap_or_default()))
+    })
+}
+
+/// Returns the contract IDs of the capabilities provided through the given wasmCloud interfaces
+fn capability_claims(interfaces: &BTreeSet<String>) -> Vec<String> {
+    interfaces
+        .iter()
+        .filter(|interface| !NON_CAPABILITY_INTERFACES.contains(&interface.as_str()))
+        .map(|interface| contract_id(interface))
+        .collect()
+}
+
+/// Returns the contract ID for a wasmCloud interface name, e.g. `wasmcloud:httpserver` for `httpserver`
+fn contract_id(interface: &str) -> String {
+    let name: String = interface
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_'))
+        .collect();
+    if BUILTIN_INTERFACES.contains(&name.as_str()) {
+        format!("wasmcloud:builtin:{name}")
+    } else {
+        format!("wasmcloud:{name}")
+    }
+}
+
+/// Returns the capability ID of a provider, which must be given unless the provider depends on exactly one
+/// wasmCloud interface
+fn provider_capability_id(options: &InitOptions, interfaces: &BTreeSet<String>) -> Result<String> {
+    if let Some(capability_id) = &options.capability_id {
+        return Ok(capability_id.clone());
+    }
+    let claims = capability_claims(interfaces);
+    match claims.as_slice() {
+        [capability_id] => Ok(capability_id.clone()),
+        [] => bail!("Couldn't infer the capability ID of the provider, please set it with --capability-id"),
+        _ => bail!(
+            "The provider depends on more than one interface ({}), please set its capability ID with --capability-id",
+            claims.join(", ")
+        ),
+    }
+}
+
+fn default_actor_config() -> ActorConfig {
+    ActorConfig {
+        key_directory: PathBuf::from("./keys"),
+        wasm_target: "wasm32-unknown-unknown".to_string(),
+        ..Default::default()
+    }
+}
+
+fn default_interface_config() -> InterfaceConfig {
+    InterfaceConfig {
+        html_target: PathBuf::from("./html"),
+        codegen_config: PathBuf::from("./codegen.toml"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_infer_claims_from_dependencies() {
+        let interfaces = [
+            "httpserver",
+            "logging",
+            "keyvalue",
+            "lattice-control",
+            "testing",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        assert_eq!(
+            capability_claims(&interfaces),
+            vec![
+                "wasmcloud:httpserver",
+                "wasmcloud:keyvalue",
+                "wasmcloud:latticecontrol",
+                "wasmcloud:builtin:logging",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_read_go_requirements() {
+        let go_mod = r#"module github.com/example/echo
+
+go 1.20
+
+require github.com/wasmcloud/actor-tinygo v0.1.4
+
+require (
+	github.com/wasmcloud/interfaces/httpserver/tinygo v0.0.0-20230110 // indirect
+	github.com/wasmcloud/tinygo-msgpack v0.1.4
+)
+"#;
+        assert_eq!(
+            go_requirements(go_mod).collect::<Vec<_>>(),
+            vec![
+                ("github.com/wasmcloud/actor-tinygo", "v0.1.4"),
+                (
+                    "github.com/wasmcloud/interfaces/httpserver/tinygo",
+                    "v0.0.0-20230110"
+                ),
+                ("github.com/wasmcloud/tinygo-msgpack", "v0.1.4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_infer_rust_actor() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join(".cargo")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"[package]
+name = "echo-actor"
+version = "0.2.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib", "rlib"]
+
+[dependencies]
+wasmbus-rpc = "0.13"
+wasmcloud-interface-httpserver = "0.10"
+wasmcloud-interface-logging = "0.9"
+
+[dev-dependencies]
+wasmcloud-interface-testing = "0.8"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".cargo/config.toml"),
+            "[build]\ntarget = \"wasm32-wasi\"\n",
+        )
+        .unwrap();
+
+        let config = infer_config(dir.path(), &InitOptions::default()).unwrap();
+        assert_eq!(config.common.name, "echo-actor");
+        assert_eq!(config.common.version, Version::parse("0.2.0").unwrap());
+        assert_eq!(config.common.wasm_bin_name, Some("echo_actor".to_string()));
+        assert_eq!(
+            config.project_type,
+            TypeConfig::Actor(ActorConfig {
+                claims: vec![
+                    "wasmcloud:httpserver".to_string(),
+                    "wasmcloud:builtin:logging".to_string()
+                ],
+                wasm_target: "wasm32-wasi".to_string(),
+                ..default_actor_config()
+            })
+        );
+
+        // The name and version come from Cargo.toml, so they are left out of the file
+        assert_eq!(
+            config.to_minimal_file_value().unwrap(),
+            serde_json::json!({
+                "language": "rust",
+                "type": "actor",
+                "actor": {
+                    "claims": ["wasmcloud:httpserver", "wasmcloud:builtin:logging"],
+                    "wasm_target": "wasm32-wasi",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn can_infer_tinygo_actor() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module github.com/example/echo\n\nrequire github.com/wasmcloud/interfaces/httpserver/tinygo v0.1.0\n",
+        )
+        .unwrap();
+
+        let config = infer_config(dir.path(), &InitOptions::default()).unwrap();
+        assert_eq!(config.common.name, "echo");
+        assert_eq!(
+            config.language,
+            LanguageConfig::TinyGo(TinyGoConfig::default())
+        );
+        assert_eq!(
+            config.project_type,
+            TypeConfig::Actor(ActorConfig {
+                claims: vec!["wasmcloud:httpserver".to_string()],
+                ..default_actor_config()
+            })
+        );
+
+        let options = InitOptions {
+            project_type: Some("provider".to_string()),
+            ..Default::default()
+        };
+        assert!(infer_config(dir.path(), &options).is_err());
+    }
+}
@@ -4,25 +4,59 @@
 use anyhow::{anyhow, bail, Result};
 use cargo_toml::{Manifest, Product};
 use config::Config;
+use schemars::JsonSchema;
 use semver::Version;
-use std::{fs, path::PathBuf};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+mod init;
+mod schema;
+mod validate;
+
+#[cfg(feature = "cli")]
+pub(crate) use init::go_requirements;
+pub use init::{infer_config, InitOptions};
+pub use schema::config_schema;
+pub use validate::{validate_config, validate_config_value, ConfigProblem};
+
+/// Name of the table in `wasmcloud.toml` containing the environment overlays, e.g. `[env.production]`
+pub const ENVIRONMENTS_KEY: &str = "env";
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum LanguageConfig {
     Rust(RustConfig),
     TinyGo(TinyGoConfig),
+    Custom(CustomConfig),
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+impl LanguageConfig {
+    /// Selects the named build profile, overriding the `profile` set in `wasmcloud.toml`
+    pub fn select_profile(&mut self, name: &str) -> Result<()> {
+        match self {
+            LanguageConfig::Rust(rust_config) => rust_config.profile = Some(name.to_string()),
+            LanguageConfig::TinyGo(tinygo_config) => tinygo_config.profile = Some(name.to_string()),
+            LanguageConfig::Custom(_) => {
+                bail!("Build profiles are not supported for projects with a custom language")
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Name of the build profile used when none is selected
+pub const DEFAULT_BUILD_PROFILE: &str = "release";
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum TypeConfig {
     Actor(ActorConfig),
     Provider(ProviderConfig),
     Interface(InterfaceConfig),
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct ProjectConfig {
     /// The language of the project, e.g. rust, tinygo. Contains specific configuration for that language.
     pub language: LanguageConfig,
@@ -34,7 +68,104 @@ pub struct ProjectConfig {
     pub common: CommonConfig,
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+impl ProjectConfig {
+    /// Returns the config in the layout of a `wasmcloud.toml` file, with every default filled in. The result can
+    /// be written to a `wasmcloud.toml` file and read back with [get_config].
+    pub fn to_file_value(&self) -> Result<Value> {
+        let mut value = json!({
+            "name": self.common.name,
+            "version": self.common.version.to_string(),
+        });
+
+        let (language, language_config) = match &self.language {
+            LanguageConfig::Rust(config) => ("rust", serde_json::to_value(config)?),
+            LanguageConfig::TinyGo(config) => ("tinygo", serde_json::to_value(config)?),
+            LanguageConfig::Custom(config) => ("custom", serde_json::to_value(config)?),
+        };
+        value["language"] = json!(language);
+        value[language] = language_config;
+
+        let (project_type, type_config) = match &self.project_type {
+            TypeConfig::Actor(config) => ("actor", serde_json::to_value(config)?),
+            TypeConfig::Provider(config) => ("provider", serde_json::to_value(config)?),
+            TypeConfig::Interface(config) => ("interface", serde_json::to_value(config)?),
+        };
+        value["type"] = json!(project_type);
+        value[project_type] = type_config;
+
+        if self.common.hooks != HooksConfig::default() {
+            value["hooks"] = serde_json::to_value(&self.common.hooks)?;
+        }
+        if self.common.dev != DevConfig::default() {
+            value["dev"] = serde_json::to_value(&self.common.dev)?;
+        }
+
+        // TOML has no null, so unset options are left out. The top level tables are kept even when empty, since
+        // the project type's table is required.
+        if let Value::Object(map) = &mut value {
+            map.values_mut().for_each(remove_empty);
+        }
+        // The payload of the dev test is sent to the test actor as it is, so its empty tables are kept
+        if let Some(test) = &self.common.dev.test {
+            value["dev"]["test"]["payload"] = test.payload.clone();
+        }
+        Ok(value)
+    }
+
+    /// Returns the config in the layout of a `wasmcloud.toml` file like [to_file_value](Self::to_file_value),
+    /// leaving out every key that is set to its default value or, for rust projects, comes from `Cargo.toml`
+    pub fn to_minimal_file_value(&self) -> Result<Value> {
+        let mut value = self.to_file_value()?;
+        let parse = |value: &Value| -> Result<ProjectConfig> {
+            serde_json::from_value::<RawProjectConfig>(value.clone())?
+                .convert(self.common.path.clone())
+        };
+        let expected = parse(&value)?;
+
+        // Keys are removed one at a time, and kept when removing them changes the config. Tables come after
+        // their keys, so a table is removed once everything in it is a default.
+        let mut pointers = Vec::new();
+        key_pointers(&value, String::new(), &mut pointers);
+        for pointer in pointers {
+            let mut candidate = value.clone();
+            let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+            if let Some(Value::Object(table)) = candidate.pointer_mut(parent) {
+                table.remove(&key.replace("~1", "/").replace("~0", "~"));
+            }
+            if parse(&candidate).map_or(false, |config| config == expected) {
+                value = candidate;
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// Collects the JSON pointers to every key in `value`, with the keys of a table before the table itself, except
+/// the language and type keys that every file needs
+fn key_pointers(value: &Value, pointer: String, pointers: &mut Vec<String>) {
+    if let Value::Object(map) = value {
+        for (key, value) in map {
+            if pointer.is_empty() && (key == "language" || key == "type") {
+                continue;
+            }
+            let key = key.replace('~', "~0").replace('/', "~1");
+            key_pointers(value, format!("{pointer}/{key}"), pointers);
+        }
+    }
+    if !pointer.is_empty() {
+        pointers.push(pointer);
+    }
+}
+
+/// Removes nulls and empty tables nested in the value
+fn remove_empty(value: &mut Value) {
+    if let Value::Object(map) = value {
+        map.values_mut().for_each(remove_empty);
+        map.retain(|_, v| !v.is_null() && v.as_object().map_or(true, |table| !table.is_empty()));
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct ActorConfig {
     /// The list of provider claims that this actor requires. eg. ["wasmcloud:httpserver", "wasmcloud:blobstore"]
     pub claims: Vec<String>,
@@ -50,10 +181,87 @@ pub struct ActorConfig {
     pub wasm_target: String,
     /// The call alias of the actor.
     pub call_alias: Option<String>,
+    /// Path to a WASI preview1 adapter module. When set, the compiled core module is turned into a WebAssembly component before signing.
+    pub wasi_adapter_path: Option<PathBuf>,
+    /// Options for optimizing the compiled module before it is signed
+    pub optimize: OptimizeConfig,
+    /// Arbitrary tags to embed in the actor's claims
+    pub tags: Vec<String>,
+    /// The revision to sign the actor with. When not set, the actor is signed without a revision.
+    pub rev: Option<Revision>,
+    /// The number of days the actor's claims are valid for. When not set, the claims never expire.
+    pub expires_in_days: Option<u64>,
+    /// The number of days that must pass before the actor's claims are valid. When not set, they are valid immediately.
+    pub not_before_days: Option<u64>,
+    /// The issuer (account) key to sign with: the name of a key in the key directory, or a path to a seed file
+    /// relative to the project directory. When not set, the default account key is used.
+    pub issuer_key: Option<String>,
+    /// The subject (module) key to sign with: the name of a key in the key directory, or a path to a seed file
+    /// relative to the project directory. When not set, a key named after the module is used.
+    pub subject_key: Option<String>,
+}
+
+/// The revision an actor is signed with
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Revision {
+    /// A fixed revision number, e.g. `rev = 3`
+    Number(i32),
+    /// One more than the revision of the previously built artifact, starting at 1. Set with `rev = "auto"`.
+    Auto,
+}
+
+/// Value of `rev` that selects [Revision::Auto]
+pub const AUTO_REVISION: &str = "auto";
+
+impl JsonSchema for Revision {
+    fn schema_name() -> String {
+        "Revision".to_string()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schema::from_json(json!({
+            "type": ["integer", "string"],
+            "minimum": 0,
+            "if": { "type": "string" },
+            "then": { "const": AUTO_REVISION },
+        }))
+    }
+}
+
+impl serde::Serialize for Revision {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Revision::Number(rev) => serializer.serialize_i32(*rev),
+            Revision::Auto => serializer.serialize_str(AUTO_REVISION),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Revision {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum RawRevision {
+            Number(i32),
+            Mode(String),
+        }
+        match RawRevision::deserialize(deserializer)? {
+            RawRevision::Number(rev) => Ok(Revision::Number(rev)),
+            RawRevision::Mode(mode) if mode.trim().eq_ignore_ascii_case(AUTO_REVISION) => {
+                Ok(Revision::Auto)
+            }
+            RawRevision::Mode(mode) => Err(serde::de::Error::custom(format!(
+                "invalid rev `{mode}`, expected a number or \"{AUTO_REVISION}\""
+            ))),
+        }
+    }
 }
-#[derive(serde::Deserialize, Debug, PartialEq)]
+
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct RawActorConfig {
     /// The list of provider claims that this actor requires. eg. ["wasmcloud:httpserver", "wasmcloud:blobstore"]
+    #[schemars(with = "Option<Vec<schema::ContractId>>")]
     pub claims: Option<Vec<String>>,
     /// The registry to push to. eg. "localhost:8080"
     pub registry: Option<String>,
@@ -67,12 +275,38 @@ struct RawActorConfig {
     pub wasm_target: Option<String>,
     /// The call alias of the actor. Defaults to no alias.
     pub call_alias: Option<String>,
+    /// Path to a WASI preview1 adapter module. Optional, when set the actor is built as a WebAssembly component.
+    pub wasi_adapter_path: Option<PathBuf>,
+    /// Options for optimizing the compiled module. Optional, defaults to no optimization.
+    pub optimize: Option<RawOptimizeConfig>,
+    /// Tags to embed in the actor's claims. Optional, defaults to none.
+    pub tags: Option<Vec<String>>,
+    /// The revision to sign the actor with, a number or "auto". Optional, defaults to no revision.
+    pub rev: Option<Revision>,
+    /// The number of days the actor's claims are valid for. Optional, defaults to never expiring.
+    pub expires_in_days: Option<u64>,
+    /// The number of days before the actor's claims are valid. Optional, defaults to valid immediately.
+    pub not_before_days: Option<u64>,
+    /// The issuer key name or seed path. Optional, defaults to the default account key.
+    pub issuer_key: Option<String>,
+    /// The subject key name or seed path. Optional, defaults to a key named after the module.
+    pub subject_key: Option<String>,
 }
 
 impl TryFrom<RawActorConfig> for ActorConfig {
     type Error = anyhow::Error;
 
     fn try_from(raw_config: RawActorConfig) -> Result<Self> {
+        let wasm_target = raw_config
+            .wasm_target
+            .unwrap_or_else(|| "wasm32-unknown-unknown".to_string());
+
+        if raw_config.wasi_adapter_path.is_some() && !is_wasi_target(&wasm_target) {
+            bail!(
+                "wasi_adapter_path can only be used with a WASI wasm_target (wasm32-wasi or wasm32-wasip1), found {wasm_target}"
+            );
+        }
+
         Ok(Self {
             claims: raw_config.claims.unwrap_or_default(),
             registry: raw_config.registry,
@@ -81,26 +315,106 @@ impl TryFrom<RawActorConfig> for ActorConfig {
                 .key_directory
                 .unwrap_or_else(|| PathBuf::from("./keys")),
             filename: raw_config.filename,
-            wasm_target: raw_config
-                .wasm_target
-                .unwrap_or_else(|| "wasm32-unknown-unknown".to_string()),
+            wasm_target,
             call_alias: raw_config.call_alias,
+            wasi_adapter_path: raw_config.wasi_adapter_path,
+            optimize: match raw_config.optimize {
+                Some(optimize_config) => optimize_config.try_into()?,
+                None => OptimizeConfig::default(),
+            },
+            tags: raw_config.tags.unwrap_or_default(),
+            rev: raw_config.rev,
+            expires_in_days: raw_config.expires_in_days,
+            not_before_days: raw_config.not_before_days,
+            issuer_key: raw_config.issuer_key,
+            subject_key: raw_config.subject_key,
+        })
+    }
+}
+
+/// Options for shrinking a compiled actor module before it is signed
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct OptimizeConfig {
+    /// Whether to strip custom sections, like names and debug information, from the module
+    pub strip: bool,
+    /// Whether to optimize the module with Binaryen's `wasm-opt`
+    pub wasm_opt: bool,
+    /// The path to the wasm-opt binary. Optional, will default to search the user's `PATH` for `wasm-opt` if not specified.
+    pub wasm_opt_path: Option<PathBuf>,
+    /// Arguments to pass to wasm-opt
+    pub wasm_opt_args: Vec<String>,
+    /// The maximum size, in bytes, of the built artifact. The build fails if the artifact is larger.
+    pub max_size: Option<u64>,
+}
+
+impl OptimizeConfig {
+    /// Returns whether any optimization of the module is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.strip || self.wasm_opt
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawOptimizeConfig {
+    /// Whether to strip custom sections from the module. Optional, defaults to false.
+    pub strip: Option<bool>,
+    /// Whether to optimize the module with wasm-opt. Optional, defaults to true when `wasm_opt_path` is set.
+    pub wasm_opt: Option<bool>,
+    /// The path to the wasm-opt binary. Optional, defaults to `wasm-opt`.
+    pub wasm_opt_path: Option<PathBuf>,
+    /// Arguments to pass to wasm-opt. Optional, defaults to ["-Oz"].
+    pub wasm_opt_args: Option<Vec<String>>,
+    /// The maximum size, in bytes, of the built artifact. Optional, defaults to no limit.
+    pub max_size: Option<u64>,
+}
+
+impl TryFrom<RawOptimizeConfig> for OptimizeConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(raw_config: RawOptimizeConfig) -> Result<Self> {
+        Ok(Self {
+            strip: raw_config.strip.unwrap_or(false),
+            wasm_opt: raw_config
+                .wasm_opt
+                .unwrap_or(raw_config.wasm_opt_path.is_some()),
+            wasm_opt_path: raw_config.wasm_opt_path,
+            wasm_opt_args: raw_config
+                .wasm_opt_args
+                .unwrap_or_else(|| vec!["-Oz".to_string()]),
+            max_size: raw_config.max_size,
         })
     }
 }
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+/// Returns whether the given wasm target triple builds against WASI preview1
+pub fn is_wasi_target(wasm_target: &str) -> bool {
+    matches!(wasm_target, "wasm32-wasi" | "wasm32-wasip1")
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct ProviderConfig {
     /// The capability ID of the provider.
     pub capability_id: String,
     /// The vendor name of the provider.
     pub vendor: String,
+    /// The list of target triples to build the provider binary for, e.g. ["x86_64-unknown-linux-gnu"].
+    /// When empty, the provider is only built for the host target.
+    pub targets: Vec<String>,
+    /// The name of the provider binary that will be generated.
+    pub bin_name: Option<String>,
 }
-#[derive(serde::Deserialize, Debug, PartialEq)]
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct RawProviderConfig {
-    /// The capability ID of the provider.
+    /// The capability contract ID of the provider, e.g. "wasmcloud:keyvalue"
+    #[schemars(with = "schema::ContractId")]
     pub capability_id: String,
     /// The vendor name of the provider. Optional, defaults to 'NoVendor'.
     pub vendor: Option<String>,
+    /// The list of target triples to build the provider binary for. Optional, defaults to the host target.
+    pub targets: Option<Vec<String>>,
+    /// The name of the provider binary. Optional, defaults to the project name.
+    pub bin_name: Option<String>,
 }
 
 impl TryFrom<RawProviderConfig> for ProviderConfig {
@@ -110,19 +424,21 @@ impl TryFrom<RawProviderConfig> for ProviderConfig {
         Ok(Self {
             capability_id: raw_config.capability_id,
             vendor: raw_config.vendor.unwrap_or_else(|| "NoVendor".to_string()),
+            targets: raw_config.targets.unwrap_or_default(),
+            bin_name: raw_config.bin_name,
         })
     }
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct InterfaceConfig {
     /// Directory to output HTML.
     pub html_target: PathBuf,
     /// Path to codegen.toml file.
     pub codegen_config: PathBuf,
 }
-#[derive(serde::Deserialize, Debug, PartialEq)]
-
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct RawInterfaceConfig {
     /// Directory to output HTML. Defaults to "./html".
     pub html_target: Option<PathBuf>,
@@ -145,20 +461,44 @@ impl TryFrom<RawInterfaceConfig> for InterfaceConfig {
     }
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct RustConfig {
     /// The path to the cargo binary. Optional, will default to search the user's `PATH` for `cargo` if not specified.
     pub cargo_path: Option<PathBuf>,
     /// Path to cargo/rust's `target` directory. Optional, defaults to the cargo target directory for the workspace or project.
     pub target_path: Option<PathBuf>,
+    /// Name of the build profile to use. When not set, [DEFAULT_BUILD_PROFILE] is used.
+    pub profile: Option<String>,
+    /// Build profiles, by name
+    pub profiles: BTreeMap<String, RustProfile>,
+}
+
+impl RustConfig {
+    /// Returns the selected build profile. Profiles that aren't configured are assumed to be cargo profiles
+    /// of the same name, built with default features and no extra arguments.
+    pub fn build_profile(&self) -> RustProfile {
+        let name = self.profile.as_deref().unwrap_or(DEFAULT_BUILD_PROFILE);
+        self.profiles
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| RustProfile {
+                cargo_profile: name.to_string(),
+                ..Default::default()
+            })
+    }
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Default, Clone)]
+#[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct RawRustConfig {
     /// The path to the cargo binary. Optional, will default to search the user's `PATH` for `cargo` if not specified.
     pub cargo_path: Option<PathBuf>,
     /// Path to cargo/rust's `target` directory. Optional, defaults to `./target`.
     pub target_path: Option<PathBuf>,
+    /// Name of the build profile to use. Optional, defaults to "release".
+    pub profile: Option<String>,
+    /// Build profiles, by name. Optional, defaults to none.
+    pub profiles: Option<BTreeMap<String, RawRustProfile>>,
 }
 
 impl TryFrom<RawRustConfig> for RustConfig {
@@ -168,12 +508,53 @@ impl TryFrom<RawRustConfig> for RustConfig {
         Ok(Self {
             cargo_path: raw_config.cargo_path,
             target_path: raw_config.target_path,
+            profile: raw_config.profile,
+            profiles: raw_config
+                .profiles
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, profile)| {
+                    let profile = RustProfile {
+                        cargo_profile: profile.cargo_profile.unwrap_or_else(|| name.clone()),
+                        features: profile.features.unwrap_or_default(),
+                        no_default_features: profile.no_default_features.unwrap_or(false),
+                        extra_args: profile.extra_args.unwrap_or_default(),
+                    };
+                    (name, profile)
+                })
+                .collect(),
         })
     }
 }
 
+/// Options for building a rust project with cargo
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct RustProfile {
+    /// The cargo profile to build with, e.g. "dev", "release", or a custom profile from Cargo.toml
+    pub cargo_profile: String,
+    /// Cargo features to enable
+    pub features: Vec<String>,
+    /// Whether to disable the default cargo features
+    pub no_default_features: bool,
+    /// Extra arguments to pass to `cargo build`
+    pub extra_args: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawRustProfile {
+    /// The cargo profile to build with. Optional, defaults to the name of the profile.
+    pub cargo_profile: Option<String>,
+    /// Cargo features to enable. Optional, defaults to none.
+    pub features: Option<Vec<String>>,
+    /// Whether to disable the default cargo features. Optional, defaults to false.
+    pub no_default_features: Option<bool>,
+    /// Extra arguments to pass to `cargo build`. Optional, defaults to none.
+    pub extra_args: Option<Vec<String>>,
+}
+
 /// Configuration common amoung all project types & languages.
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct CommonConfig {
     /// Name of the project.
     pub name: String,
@@ -184,37 +565,224 @@ pub struct CommonConfig {
     /// Expected name of the wasm module binary that will be generated
     /// (if not present, name is expected to be used as a fallback)
     pub wasm_bin_name: Option<String>,
+    /// Commands to run at different stages of building the project
+    pub hooks: HooksConfig,
+    /// Providers and links that `wash dev` sets up for the project
+    pub dev: DevConfig,
+}
+
+/// Commands that are run, in order, at different stages of `wash build`. Each command is run with the system
+/// shell in the project directory.
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct HooksConfig {
+    /// Commands to run before the project is compiled
+    pub pre_build: Vec<String>,
+    /// Commands to run after the project is compiled, before it is signed
+    pub post_build: Vec<String>,
+    /// Commands to run after the project artifact is signed
+    pub post_sign: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawHooksConfig {
+    /// Commands to run before the project is compiled. Optional, defaults to none.
+    pub pre_build: Option<Vec<String>>,
+    /// Commands to run after the project is compiled. Optional, defaults to none.
+    pub post_build: Option<Vec<String>>,
+    /// Commands to run after the project artifact is signed. Optional, defaults to none.
+    pub post_sign: Option<Vec<String>>,
+}
+
+impl TryFrom<RawHooksConfig> for HooksConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(raw_config: RawHooksConfig) -> Result<Self> {
+        Ok(Self {
+            pre_build: raw_config.pre_build.unwrap_or_default(),
+            post_build: raw_config.post_build.unwrap_or_default(),
+            post_sign: raw_config.post_sign.unwrap_or_default(),
+        })
+    }
+}
+
+/// The environment `wash dev` sets up on the dev host: providers that are started and linked to the actor under
+/// development, and the test actor invoked after each reload
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct DevConfig {
+    /// Providers to start on the dev host
+    pub providers: Vec<DevProviderConfig>,
+    /// Test actor to invoke after each reload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<DevTestConfig>,
+}
+
+/// Operation of the `wasmcloud:testing` interface, which runs the tests of a test actor
+pub const DEFAULT_DEV_TEST_OPERATION: &str = "Testing.Start";
+/// Default time to wait for the results of a test actor, in milliseconds
+pub const DEFAULT_DEV_TEST_TIMEOUT_MS: u64 = 10_000;
+
+/// A test actor that `wash dev` invokes after each reload, decoding its response as test results
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DevTestConfig {
+    /// Reference of the test actor, e.g. an OCI reference or a `file://` URL, which is started on the dev host, or
+    /// the ID of an actor already running. The actor under development when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Operation to invoke on the test actor
+    pub operation: String,
+    /// Payload of the invocation
+    pub payload: serde_json::Value,
+    /// Time to wait for the test results, in milliseconds
+    pub timeout_ms: u64,
+}
+
+/// A provider started by `wash dev`
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct DevProviderConfig {
+    /// Reference of the provider, e.g. an OCI reference or a `file://` URL
+    pub image_ref: String,
+    /// Capability contract ID of the provider, e.g. "wasmcloud:httpserver"
+    pub contract_id: String,
+    /// Link name of the provider
+    pub link_name: String,
+    /// Values of the link between the provider and the actor under development
+    pub link_values: BTreeMap<String, String>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Debug, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawDevConfig {
+    /// Providers to start on the dev host. Optional, defaults to none.
+    pub providers: Option<Vec<RawDevProviderConfig>>,
+    /// Test actor to invoke after each reload. Optional, defaults to none.
+    pub test: Option<RawDevTestConfig>,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawDevTestConfig {
+    /// Reference or ID of the test actor. Optional, defaults to the actor under development.
+    pub actor: Option<String>,
+    /// Operation to invoke. Optional, defaults to 'Testing.Start'.
+    pub operation: Option<String>,
+    /// Payload of the invocation. Optional, defaults to running every test of a `wasmcloud:testing` actor.
+    pub payload: Option<serde_json::Value>,
+    /// Time to wait for the results, in milliseconds. Optional, defaults to 10000.
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawDevProviderConfig {
+    /// Reference of the provider.
+    pub image_ref: String,
+    /// Capability contract ID of the provider.
+    #[schemars(with = "schema::ContractId")]
+    pub contract_id: String,
+    /// Link name of the provider. Optional, defaults to 'default'.
+    pub link_name: Option<String>,
+    /// Values of the link to the actor under development. Optional, defaults to none.
+    pub link_values: Option<BTreeMap<String, String>>,
+}
+
+impl TryFrom<RawDevConfig> for DevConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(raw_config: RawDevConfig) -> Result<Self> {
+        Ok(Self {
+            providers: raw_config
+                .providers
+                .unwrap_or_default()
+                .into_iter()
+                .map(|provider| DevProviderConfig {
+                    image_ref: provider.image_ref,
+                    contract_id: provider.contract_id,
+                    link_name: provider.link_name.unwrap_or_else(|| "default".to_string()),
+                    link_values: provider.link_values.unwrap_or_default(),
+                })
+                .collect(),
+            test: raw_config.test.map(|test| DevTestConfig {
+                actor: test.actor,
+                operation: test
+                    .operation
+                    .unwrap_or_else(|| DEFAULT_DEV_TEST_OPERATION.to_string()),
+                payload: test
+                    .payload
+                    .unwrap_or_else(|| serde_json::json!({ "patterns": [".*"], "options": {} })),
+                timeout_ms: test.timeout_ms.unwrap_or(DEFAULT_DEV_TEST_TIMEOUT_MS),
+            }),
+        })
+    }
+}
+
+/// Configuration for building and signing a wasmCloud actor, provider, or interface
+#[derive(serde::Deserialize, Debug, JsonSchema)]
+#[schemars(title = "wasmcloud.toml", deny_unknown_fields)]
 struct RawProjectConfig {
     /// The language of the project, e.g. rust, tinygo. This is used to determine which config to parse.
+    #[schemars(with = "schema::Language")]
     pub language: String,
     /// The type of project. This is a string that is used to determine which type of config to parse.
-    /// The toml file name is just "type" but is named project_type here to avoid clashing with the type keyword in Rust.
+    // The toml file name is just "type" but is named project_type here to avoid clashing with the type keyword in Rust.
     #[serde(rename = "type")]
+    #[schemars(with = "schema::ProjectType")]
     pub project_type: String,
-    /// Name of the project.
+    /// Name of the project. Optional for rust projects, defaults to the package name in Cargo.toml.
     pub name: Option<String>,
-    /// Semantic version of the project.
+    /// Semantic version of the project, e.g. "0.1.0". Optional for rust projects, defaults to the package version in Cargo.toml.
+    #[schemars(with = "Option<schema::SemverString>")]
     pub version: Option<Version>,
+    /// Configuration for actor projects
     pub actor: Option<RawActorConfig>,
+    /// Configuration for capability provider projects
     pub provider: Option<RawProviderConfig>,
+    /// Configuration for building rust projects
     pub rust: Option<RawRustConfig>,
+    /// Configuration for interface projects
     pub interface: Option<RawInterfaceConfig>,
+    /// Configuration for building tinygo projects
     pub tinygo: Option<RawTinyGoConfig>,
+    /// Configuration for building projects in a language without built-in support
+    pub custom: Option<RawCustomConfig>,
+    /// Commands run with the system shell in the project directory at each stage of the build
+    pub hooks: Option<RawHooksConfig>,
+    /// Providers that `wash dev` starts on the dev host and links to the actor under development, and the test actor it invokes after each reload
+    pub dev: Option<RawDevConfig>,
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct TinyGoConfig {
     /// The path to the tinygo binary. Optional, will default to `tinygo` if not specified.
     pub tinygo_path: Option<PathBuf>,
+    /// Name of the build profile to use. When not set, [DEFAULT_BUILD_PROFILE] is used.
+    pub profile: Option<String>,
+    /// Build profiles, by name
+    pub profiles: BTreeMap<String, TinyGoProfile>,
+}
+
+impl TinyGoConfig {
+    /// Returns the selected build profile. The default profile doesn't need to be configured, but any other
+    /// selected profile must be.
+    pub fn build_profile(&self) -> Result<TinyGoProfile> {
+        let name = self.profile.as_deref().unwrap_or(DEFAULT_BUILD_PROFILE);
+        match self.profiles.get(name) {
+            Some(profile) => Ok(profile.clone()),
+            None if name == DEFAULT_BUILD_PROFILE => Ok(TinyGoProfile::default()),
+            None => bail!("Unknown tinygo build profile: {name}"),
+        }
+    }
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Default)]
+#[derive(serde::Deserialize, Debug, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct RawTinyGoConfig {
     /// The path to the tinygo binary. Optional, will default to `tinygo` if not specified.
     pub tinygo_path: Option<PathBuf>,
+    /// Name of the build profile to use. Optional, defaults to "release".
+    pub profile: Option<String>,
+    /// Build profiles, by name. Optional, defaults to none.
+    pub profiles: Option<BTreeMap<String, RawTinyGoProfile>>,
 }
 
 impl TryFrom<RawTinyGoConfig> for TinyGoConfig {
@@ -223,6 +791,105 @@ impl TryFrom<RawTinyGoConfig> for TinyGoConfig {
     fn try_from(raw_config: RawTinyGoConfig) -> Result<Self> {
         Ok(Self {
             tinygo_path: raw_config.tinygo_path,
+            profile: raw_config.profile,
+            profiles: raw_config
+                .profiles
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, profile)| {
+                    let defaults = TinyGoProfile::default();
+                    let profile = TinyGoProfile {
+                        gc: profile.gc,
+                        scheduler: profile.scheduler.unwrap_or(defaults.scheduler),
+                        no_debug: profile.no_debug.unwrap_or(defaults.no_debug),
+                        extra_args: profile.extra_args.unwrap_or_default(),
+                    };
+                    (name, profile)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Options for building a tinygo project
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct TinyGoProfile {
+    /// The garbage collector to use, e.g. "leaking" or "conservative". Uses the tinygo default when not set.
+    pub gc: Option<String>,
+    /// The scheduler to use, e.g. "none" or "asyncify"
+    pub scheduler: String,
+    /// Whether to strip debug information from the output
+    pub no_debug: bool,
+    /// Extra arguments to pass to `tinygo build`
+    pub extra_args: Vec<String>,
+}
+
+impl Default for TinyGoProfile {
+    fn default() -> Self {
+        Self {
+            gc: None,
+            scheduler: "none".to_string(),
+            no_debug: true,
+            extra_args: Vec::new(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawTinyGoProfile {
+    /// The garbage collector to use. Optional, defaults to the tinygo default.
+    pub gc: Option<String>,
+    /// The scheduler to use. Optional, defaults to "none".
+    pub scheduler: Option<String>,
+    /// Whether to strip debug information from the output. Optional, defaults to true.
+    pub no_debug: Option<bool>,
+    /// Extra arguments to pass to `tinygo build`. Optional, defaults to none.
+    pub extra_args: Option<Vec<String>>,
+}
+
+/// Configuration for projects written in a language without built-in support, which are built by running
+/// an arbitrary command
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct CustomConfig {
+    /// The command used to build the project, run with the system shell, e.g. "npm run asbuild"
+    pub build_command: String,
+    /// The directory to run the build command in, relative to the project directory
+    pub working_dir: PathBuf,
+    /// Environment variables to set for the build command
+    pub env: BTreeMap<String, String>,
+    /// Path to the Wasm module produced by the build command, relative to the project directory
+    pub output_path: PathBuf,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawCustomConfig {
+    /// The command used to build the project, run with the system shell.
+    #[schemars(required)]
+    pub build_command: Option<String>,
+    /// The directory to run the build command in. Optional, defaults to the project directory.
+    pub working_dir: Option<PathBuf>,
+    /// Environment variables to set for the build command. Optional, defaults to none.
+    pub env: Option<BTreeMap<String, String>>,
+    /// Path to the Wasm module produced by the build command.
+    #[schemars(required)]
+    pub output_path: Option<PathBuf>,
+}
+
+impl TryFrom<RawCustomConfig> for CustomConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(raw_config: RawCustomConfig) -> Result<Self> {
+        Ok(Self {
+            build_command: raw_config
+                .build_command
+                .ok_or_else(|| anyhow!("Missing build_command in custom language config"))?,
+            working_dir: raw_config.working_dir.unwrap_or_else(|| PathBuf::from(".")),
+            env: raw_config.env.unwrap_or_default(),
+            output_path: raw_config
+                .output_path
+                .ok_or_else(|| anyhow!("Missing output_path in custom language config"))?,
         })
     }
 }
@@ -238,6 +905,128 @@ impl TryFrom<RawTinyGoConfig> for TinyGoConfig {
 /// * `opt_path` - The path to the config file. If None, it will look for a wasmcloud.toml file in the current directory.
 /// * `use_env` - Whether to use the environment variables or not. If false, it will not attempt to use environment variables. Defaults to true.
 pub fn get_config(opt_path: Option<PathBuf>, use_env: Option<bool>) -> Result<ProjectConfig> {
+    get_config_for_environment(opt_path, use_env, None)
+}
+
+/// Gets the wasmCloud project config like [get_config], with the named environment overlay applied.
+///
+/// Environment overlays are tables under `[env.<name>]` in `wasmcloud.toml` that contain any of the keys of the
+/// file itself, e.g. `[env.production.actor]`. The selected overlay is deep-merged over the rest of the file:
+/// tables are merged key by key, and any other value (including lists) replaces the value in the file.
+/// Environment variables prefixed with "WASMCLOUD_" are applied after the overlay.
+///
+/// # Arguments
+/// * `opt_path` - The path to the config file. If None, it will look for a wasmcloud.toml file in the current directory.
+/// * `use_env` - Whether to use the environment variables or not. Defaults to true.
+/// * `environment` - The name of the environment overlay to apply, if any. It is an error if the overlay doesn't exist.
+pub fn get_config_for_environment(
+    opt_path: Option<PathBuf>,
+    use_env: Option<bool>,
+    environment: Option<&str>,
+) -> Result<ProjectConfig> {
+    let (project_path, wasmcloud_path) = find_config_file(opt_path)?;
+
+    let file_value = Config::builder()
+        .add_source(config::File::from(wasmcloud_path.clone()))
+        .build()
+        .map_err(|e| {
+            if e.to_string().contains("is not of a registered file format") {
+                return anyhow!("Invalid config file: {}", wasmcloud_path.display());
+            }
+
+            anyhow!("{}", e)
+        })?
+        .try_deserialize::<Value>()?;
+    let mut json_value = apply_environment(file_value, environment)
+        .map_err(|e| anyhow!("{} in {}", e, wasmcloud_path.display()))?;
+
+    if use_env.unwrap_or(true) {
+        let env_value = Config::builder()
+            .add_source(config::Environment::with_prefix("WASMCLOUD"))
+            .build()?
+            .try_deserialize::<Value>()?;
+        merge_values(&mut json_value, env_value);
+    }
+
+    // Deserialization errors only describe the first problem, so report everything that's wrong instead
+    let raw_project_config: RawProjectConfig = serde_json::from_value(json_value.clone())
+        .map_err(|e| validate::problems_error(&json_value, &wasmcloud_path).unwrap_or(e.into()))?;
+
+    raw_project_config
+        .convert(project_path)
+        .map_err(|e: anyhow::Error| anyhow!("{} in {}", e, wasmcloud_path.display()))
+}
+
+/// Returns the names of the environment overlays defined in the `wasmcloud.toml` file at `opt_path`, or in the
+/// current directory
+pub fn config_environments(opt_path: Option<PathBuf>) -> Result<Vec<String>> {
+    let (_, wasmcloud_path) = find_config_file(opt_path)?;
+    let value: Value = toml::from_str(&fs::read_to_string(wasmcloud_path)?)?;
+    Ok(value
+        .get(ENVIRONMENTS_KEY)
+        .and_then(Value::as_object)
+        .map(|environments| environments.keys().cloned().collect())
+        .unwrap_or_default())
+}
+
+/// Removes the environment overlays from the contents of a `wasmcloud.toml` file, and merges the selected
+/// overlay over the rest of the file
+fn apply_environment(mut value: Value, environment: Option<&str>) -> Result<Value> {
+    let environments = value
+        .as_object_mut()
+        .and_then(|table| table.remove(ENVIRONMENTS_KEY));
+    let name = match environment {
+        Some(name) => name,
+        None => return Ok(value),
+    };
+
+    let overlay = environments
+        .as_ref()
+        .and_then(|environments| environments.get(name))
+        .cloned();
+    match overlay {
+        Some(overlay) => {
+            merge_values(&mut value, overlay);
+            Ok(value)
+        }
+        None => {
+            let available = environments
+                .as_ref()
+                .and_then(Value::as_object)
+                .map(|environments| environments.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            if available.is_empty() {
+                bail!(
+                    "Unknown environment {name}, no [{ENVIRONMENTS_KEY}.<name>] tables are defined"
+                )
+            }
+            bail!(
+                "Unknown environment {name}, expected one of: {}",
+                available.join(", ")
+            )
+        }
+    }
+}
+
+/// Deep-merges `overlay` into `base`. Tables are merged key by key, any other value replaces the one in `base`.
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Resolves the path given to [get_config] into the project directory and the path to its `wasmcloud.toml` file
+fn find_config_file(opt_path: Option<PathBuf>) -> Result<(PathBuf, PathBuf)> {
     let mut path = opt_path.unwrap_or_else(|| PathBuf::from("."));
 
     if !path.exists() {
@@ -268,28 +1057,7 @@ pub fn get_config(opt_path: Option<PathBuf>, use_env: Option<bool>) -> Result<Pr
         ));
     };
 
-    let mut config = Config::builder().add_source(config::File::from(wasmcloud_path.clone()));
-
-    if use_env.unwrap_or(true) {
-        config = config.add_source(config::Environment::with_prefix("WASMCLOUD"));
-    }
-
-    let json_value = config
-        .build()
-        .map_err(|e| {
-            if e.to_string().contains("is not of a registered file format") {
-                return anyhow!("Invalid config file: {}", wasmcloud_path.display());
-            }
-
-            anyhow!("{}", e)
-        })?
-        .try_deserialize::<serde_json::Value>()?;
-
-    let raw_project_config: RawProjectConfig = serde_json::from_value(json_value)?;
-
-    raw_project_config
-        .convert(project_path)
-        .map_err(|e: anyhow::Error| anyhow!("{} in {}", e, wasmcloud_path.display()))
+    Ok((project_path, wasmcloud_path))
 }
 
 impl RawProjectConfig {
@@ -338,10 +1106,21 @@ impl RawProjectConfig {
             version,
             path: project_path,
             wasm_bin_name,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
         })
     }
 
     pub fn convert(self, project_path: PathBuf) -> Result<ProjectConfig> {
+        let hooks = match self.hooks {
+            Some(hooks_config) => hooks_config.try_into()?,
+            None => HooksConfig::default(),
+        };
+        let dev = match self.dev {
+            Some(dev_config) => dev_config.try_into()?,
+            None => DevConfig::default(),
+        };
+
         let project_type_config = match self.project_type.trim().to_lowercase().as_str() {
             "actor" => {
                 let actor_config = self.actor.ok_or_else(|| anyhow!("Missing actor config"))?;
@@ -376,6 +1155,12 @@ impl RawProjectConfig {
                 Some(tinygo_config) => LanguageConfig::TinyGo(tinygo_config.try_into()?),
                 None => LanguageConfig::TinyGo(TinyGoConfig::default()),
             },
+            "custom" => {
+                let custom_config = self
+                    .custom
+                    .ok_or_else(|| anyhow!("Missing custom config"))?;
+                LanguageConfig::Custom(custom_config.try_into()?)
+            }
             _ => {
                 return Err(anyhow!(
                     "Unknown language in wasmcloud.toml: {}",
@@ -400,6 +1185,8 @@ impl RawProjectConfig {
                         version: self.version.unwrap(),
                         path: project_path,
                         wasm_bin_name: None,
+                        hooks: HooksConfig::default(),
+                        dev: DevConfig::default(),
                     }),
 
                     Err(err) => {
@@ -408,7 +1195,7 @@ impl RawProjectConfig {
                 }
             }
 
-            LanguageConfig::TinyGo(_) => Ok(CommonConfig {
+            LanguageConfig::TinyGo(_) | LanguageConfig::Custom(_) => Ok(CommonConfig {
                 name: self
                     .name
                     .ok_or_else(|| anyhow!("Missing name in wasmcloud.toml"))?,
@@ -417,13 +1204,19 @@ impl RawProjectConfig {
                     .ok_or_else(|| anyhow!("Missing version in wasmcloud.toml"))?,
                 path: project_path,
                 wasm_bin_name: None,
+                hooks: HooksConfig::default(),
+                dev: DevConfig::default(),
             }),
         };
 
+        let mut common_config = common_config_result?;
+        common_config.hooks = hooks;
+        common_config.dev = dev;
+
         Ok(ProjectConfig {
             language: language_config,
             project_type: project_type_config,
-            common: common_config_result?,
+            common: common_config,
         })
     }
 }
@@ -0,0 +1,151 @@
+//! JSON Schema describing `wasmcloud.toml` files, for editor autocompletion and validation. The schema is derived
+//! from the raw config types, then extended with the rules that span several keys.
+
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema,
+};
+use serde_json::{json, Value};
+
+use super::{RawProjectConfig, ENVIRONMENTS_KEY};
+
+/// Custom `format` for strings that must be a semantic version
+pub(crate) const SEMVER_FORMAT: &str = "semver";
+/// Custom `format` for strings that must be a capability contract ID, e.g. `wasmcloud:httpserver`
+pub(crate) const CONTRACT_ID_FORMAT: &str = "contract-id";
+
+/// Returns the JSON Schema (draft 7) for `wasmcloud.toml`. Every key accepted by [get_config](super::get_config)
+/// is described here, so keys that aren't in the schema are reported as unknown by
+/// [validate_config](super::validate_config).
+pub fn config_schema() -> Value {
+    // The validator doesn't resolve `$ref`s, so every sub-schema is inlined
+    let settings = SchemaSettings::draft07().with(|settings| {
+        settings.option_nullable = false;
+        settings.option_add_null_type = false;
+        settings.inline_subschemas = true;
+    });
+    let root = settings
+        .into_generator()
+        .into_root_schema_for::<RawProjectConfig>();
+    let mut schema = serde_json::to_value(root).expect("schema should serialize to JSON");
+
+    // A WASI adapter can only adapt modules built for WASI
+    schema["properties"]["actor"]["if"] = json!({ "required": ["wasi_adapter_path"] });
+    schema["properties"]["actor"]["then"] = json!({
+        "required": ["wasm_target"],
+        "properties": {
+            "wasm_target": { "enum": ["wasm32-wasi", "wasm32-wasip1"] },
+        },
+    });
+    schema["allOf"] = json!([
+        requires_table("type", "actor", "actor"),
+        requires_table("type", "provider", "provider"),
+        requires_table("type", "interface", "interface"),
+        requires_table("language", "custom", "custom"),
+        {
+            // Only rust projects can take their name and version from Cargo.toml
+            "if": {
+                "required": ["language"],
+                "properties": { "language": { "enum": ["tinygo", "custom"] } },
+            },
+            "then": { "required": ["name", "version"] },
+        },
+    ]);
+
+    // Overlays can contain any of the keys of the file, and don't need to repeat the required ones
+    let mut overlay = json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": schema["properties"].clone(),
+    });
+    remove_requirements(&mut overlay);
+    schema["properties"][ENVIRONMENTS_KEY] = json!({
+        "type": "object",
+        "description": "Named environments, selected with `wash build --env <name>` or WASH_ENV, whose settings are merged over the rest of the file",
+        "additionalProperties": overlay,
+    });
+    schema
+}
+
+/// Converts a hand-written JSON schema for the types that can't derive theirs
+pub(crate) fn from_json(schema: Value) -> Schema {
+    serde_json::from_value(schema).expect("hand-written schema should be valid")
+}
+
+/// Schema of a string that must be a semantic version
+pub(crate) struct SemverString;
+
+impl JsonSchema for SemverString {
+    fn schema_name() -> String {
+        "SemverString".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        from_json(json!({ "type": "string", "format": SEMVER_FORMAT }))
+    }
+}
+
+/// Schema of a string that must be a capability contract ID
+pub(crate) struct ContractId;
+
+impl JsonSchema for ContractId {
+    fn schema_name() -> String {
+        "ContractId".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        from_json(json!({ "type": "string", "format": CONTRACT_ID_FORMAT }))
+    }
+}
+
+/// Schema of the `language` key
+pub(crate) struct Language;
+
+impl JsonSchema for Language {
+    fn schema_name() -> String {
+        "Language".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        from_json(json!({ "type": "string", "enum": ["rust", "tinygo", "custom"] }))
+    }
+}
+
+/// Schema of the `type` key
+pub(crate) struct ProjectType;
+
+impl JsonSchema for ProjectType {
+    fn schema_name() -> String {
+        "ProjectType".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        from_json(json!({ "type": "string", "enum": ["actor", "provider", "interface"] }))
+    }
+}
+
+/// Removes every keyword that requires a key to be present from the schema and its sub-schemas
+fn remove_requirements(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            for keyword in ["required", "allOf", "if", "then"] {
+                map.remove(keyword);
+            }
+            map.values_mut().for_each(remove_requirements);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_requirements),
+        _ => {}
+    }
+}
+
+/// Returns a schema requiring the `table` key when the `key` is set to `value`
+fn requires_table(key: &str, value: &str, table: &str) -> Value {
+    json!({
+        "if": {
+            "required": [key],
+            "properties": { key: { "const": value } },
+        },
+        "then": { "required": [table] },
+    })
+}
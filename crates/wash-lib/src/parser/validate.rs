@@ -0,0 +1,434 @@
+//! Validate `wasmcloud.toml` files against the [config_schema], reporting every problem at once
+
+use std::{fmt, fs, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use serde_json::{Map, Value};
+
+use super::{
+    find_config_file, get_config_for_environment,
+    schema::{config_schema, CONTRACT_ID_FORMAT, SEMVER_FORMAT},
+};
+use crate::id::validate_contract_id;
+
+/// Maximum edit distance between a misspelled key or value and the one it is suggested to be
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// A problem found in a `wasmcloud.toml` file
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ConfigProblem {
+    /// Path to the key with the problem, e.g. `actor.claims[0]`. Empty for problems with the file as a whole.
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+    /// How the problem might be fixed
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({suggestion})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates the `wasmcloud.toml` file at `opt_path` (or in the current directory, like
+/// [get_config](super::get_config)) and returns every problem found, or an empty list if the file is valid.
+///
+/// The file, including all of its environment overlays, is first checked against the [config_schema]. If that
+/// finds no problems, the project config is resolved with the given `environment` overlay (but without
+/// environment variable overrides) to catch problems outside of the file, like a missing `Cargo.toml`.
+pub fn validate_config(
+    opt_path: Option<PathBuf>,
+    environment: Option<&str>,
+) -> Result<Vec<ConfigProblem>> {
+    let (_, wasmcloud_path) = find_config_file(opt_path)?;
+    let contents = fs::read_to_string(&wasmcloud_path)
+        .with_context(|| format!("Failed to read {}", wasmcloud_path.display()))?;
+    let value: Value = match toml::from_str(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            return Ok(vec![ConfigProblem {
+                path: String::new(),
+                message: format!("invalid TOML: {}", e.message()),
+                suggestion: e
+                    .span()
+                    .map(|span| format!("at line {}", line_number(&contents, span.start))),
+            }])
+        }
+    };
+
+    let problems = validate_config_value(&value);
+    if !problems.is_empty() {
+        return Ok(problems);
+    }
+
+    Ok(
+        match get_config_for_environment(Some(wasmcloud_path), Some(false), environment) {
+            Ok(_) => Vec::new(),
+            Err(e) => vec![ConfigProblem {
+                path: String::new(),
+                message: e.to_string(),
+                suggestion: None,
+            }],
+        },
+    )
+}
+
+/// Validates the contents of a `wasmcloud.toml` file against the [config_schema]
+pub fn validate_config_value(value: &Value) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    check(&config_schema(), value, "", &mut problems, None);
+    problems
+}
+
+/// Checks `value` against the subset of JSON Schema used by the [config_schema], adding any problems found.
+///
+/// Conditional sub-schemas only list the keys they require, so the properties of the schema containing them
+/// are passed as `outer_properties` to describe those keys.
+fn check(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    problems: &mut Vec<ConfigProblem>,
+    outer_properties: Option<&Map<String, Value>>,
+) {
+    // The type is either a single type name or a list of them
+    let expected_types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(expected)) => vec![expected.as_str()],
+        Some(Value::Array(expected)) => expected.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !expected_types.is_empty() && !expected_types.iter().any(|t| is_type(value, t)) {
+        let expected: Vec<String> = expected_types.iter().map(|t| article(t)).collect();
+        problems.push(problem(
+            path,
+            format!(
+                "expected {}, found {}",
+                expected.join(" or "),
+                type_name(value)
+            ),
+            None,
+        ));
+        return;
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if !values_match(expected, value) {
+            problems.push(problem(path, format!("expected {expected}"), None));
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.iter().any(|a| values_match(a, value)) {
+            let names: Vec<&str> = allowed.iter().filter_map(Value::as_str).collect();
+            let found = value
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or(value.to_string());
+            problems.push(problem(
+                path,
+                format!("invalid value `{found}`"),
+                Some(suggest(&found, &names)),
+            ));
+        }
+    }
+
+    if let (Some(format), Some(s)) = (schema.get("format").and_then(Value::as_str), value.as_str())
+    {
+        check_format(format, s, path, problems);
+    }
+
+    // Derived schemas write integer minimums as floats, e.g. `0.0`
+    if let (Some(minimum), Some(n)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_i64(),
+    ) {
+        let minimum = minimum as i64;
+        if n < minimum {
+            problems.push(problem(
+                path,
+                format!("{n} is less than the minimum of {minimum}"),
+                None,
+            ));
+        }
+    }
+
+    if let (Some(item_schema), Some(items)) = (schema.get("items"), value.as_array()) {
+        for (idx, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{path}[{idx}]"), problems, None);
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        check_object(schema, object, path, problems, outer_properties);
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for sub_schema in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        check(sub_schema, value, path, problems, properties);
+    }
+
+    if let (Some(condition), Some(then)) = (schema.get("if"), schema.get("then")) {
+        let mut condition_problems = Vec::new();
+        check(condition, value, path, &mut condition_problems, None);
+        if condition_problems.is_empty() {
+            check(then, value, path, problems, properties.or(outer_properties));
+        }
+    }
+}
+
+fn check_object(
+    schema: &Value,
+    object: &Map<String, Value>,
+    path: &str,
+    problems: &mut Vec<ConfigProblem>,
+    outer_properties: Option<&Map<String, Value>>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for key in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if object.contains_key(key) {
+            continue;
+        }
+        // The key may be described by this schema, or by the schema this one is a condition of
+        let described = |field: &str| {
+            [properties, outer_properties]
+                .into_iter()
+                .flatten()
+                .find_map(|p| p.get(key).and_then(|property| property.get(field)))
+        };
+        let is_table = described("type").map(|t| t == "object").unwrap_or(false);
+        let (message, mut suggestion) = if is_table {
+            let table = join_path(path, key);
+            (
+                format!("missing required table [{table}]"),
+                format!("add a [{table}] table"),
+            )
+        } else {
+            (
+                format!("missing required key `{key}`"),
+                format!("add `{key}`"),
+            )
+        };
+        if let Some(description) = described("description").and_then(Value::as_str) {
+            suggestion.push_str(&format!(": {description}"));
+        }
+        problems.push(problem(&join_path(path, key), message, Some(suggestion)));
+    }
+
+    let additional = schema.get("additionalProperties");
+    for (key, value) in object {
+        let key_path = join_path(path, key);
+        match (properties.and_then(|p| p.get(key)), additional) {
+            (Some(property), _) => check(property, value, &key_path, problems, None),
+            (None, Some(Value::Bool(false))) => {
+                let known: Vec<&str> = properties
+                    .map(|p| p.keys().map(String::as_str).collect())
+                    .unwrap_or_default();
+                problems.push(problem(
+                    &key_path,
+                    format!("unknown key `{key}`"),
+                    Some(suggest(key, &known)),
+                ));
+            }
+            (None, Some(additional_schema)) if additional_schema.is_object() => {
+                check(additional_schema, value, &key_path, problems, None)
+            }
+            // Properties not described by a sub-schema (like the ones inside `if`) are allowed
+            (None, _) => {}
+        }
+    }
+}
+
+fn check_format(format: &str, value: &str, path: &str, problems: &mut Vec<ConfigProblem>) {
+    match format {
+        SEMVER_FORMAT => {
+            if let Err(e) = Version::parse(value) {
+                problems.push(problem(
+                    path,
+                    format!("`{value}` is not a valid semantic version: {e}"),
+                    Some("use a version like \"0.1.0\"".to_string()),
+                ));
+            }
+        }
+        CONTRACT_ID_FORMAT => {
+            if let Err(e) = validate_contract_id(value) {
+                problems.push(problem(
+                    path,
+                    format!("`{value}` is not a valid contract ID"),
+                    Some(e.to_string()),
+                ));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn problem(path: &str, message: String, suggestion: Option<String>) -> ConfigProblem {
+    ConfigProblem {
+        path: path.to_string(),
+        message,
+        suggestion,
+    }
+}
+
+/// Suggests the closest of the `candidates` to `found`, or lists them all if none are close
+fn suggest(found: &str, candidates: &[&str]) -> String {
+    let closest = candidates
+        .iter()
+        .map(|c| (strsim::levenshtein(&found.to_lowercase(), c), c))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance);
+    match closest {
+        Some((_, candidate)) => format!("did you mean `{candidate}`?"),
+        None => format!("expected one of: {}", candidates.join(", ")),
+    }
+}
+
+/// Returns whether the value matches the expected `const` or `enum` value. Strings are matched the same way
+/// the parser matches them, ignoring case and surrounding whitespace.
+fn values_match(expected: &Value, value: &Value) -> bool {
+    match (expected.as_str(), value.as_str()) {
+        (Some(expected), Some(value)) => value.trim().eq_ignore_ascii_case(expected),
+        _ => expected == value,
+    }
+}
+
+fn is_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "nothing",
+        Value::Bool(_) => "a boolean",
+        Value::Number(n) if n.is_f64() => "a float",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "a table",
+    }
+}
+
+fn article(type_name: &str) -> String {
+    match type_name {
+        "object" => "a table".to_string(),
+        "array" | "integer" => format!("an {type_name}"),
+        _ => format!("a {type_name}"),
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn line_number(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())]
+        .lines()
+        .count()
+        .max(1)
+}
+
+/// Returns an error listing every problem found in the config, if there are any
+pub(crate) fn problems_error(
+    value: &Value,
+    wasmcloud_path: &std::path::Path,
+) -> Option<anyhow::Error> {
+    let problems = validate_config_value(value);
+    if problems.is_empty() {
+        return None;
+    }
+    let problems = problems
+        .iter()
+        .map(|problem| format!("  - {problem}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(anyhow!("Invalid {}:\n{problems}", wasmcloud_path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reports_every_problem_with_a_path_and_suggestion() {
+        let problems = validate_config_value(&json!({
+            "language": "rsut",
+            "type": "provider",
+            "version": "1.0",
+            "actor": { "claims": ["wasmcloud:httpserver", "VAP3UXLG6NFZPOYXSI2EAXMJHZ2A2ZD3ZSSVYO3WHEUIMJTTRKQKSNXK"] },
+            "provider": { "vendr": "acme" },
+        }));
+        let rendered: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "actor.claims[1]: `VAP3UXLG6NFZPOYXSI2EAXMJHZ2A2ZD3ZSSVYO3WHEUIMJTTRKQKSNXK` is not a valid contract ID (It looks like you used an Actor or Provider ID (e.g. VABC...) instead of a contract ID (e.g. wasmcloud:httpserver))",
+                "language: invalid value `rsut` (did you mean `rust`?)",
+                "provider.capability_id: missing required key `capability_id` (add `capability_id`: The capability contract ID of the provider, e.g. \"wasmcloud:keyvalue\")",
+                "provider.vendr: unknown key `vendr` (did you mean `vendor`?)",
+                "version: `1.0` is not a valid semantic version: unexpected end of input while parsing minor version number (use a version like \"0.1.0\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_conditional_requirements() {
+        let problems = validate_config_value(&json!({
+            "language": "TinyGo",
+            "type": "actor",
+            "actor": { "wasi_adapter_path": "adapter.wasm" },
+        }));
+        let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["actor.wasm_target", "name", "version"]);
+        assert_eq!(
+            problems[0].suggestion.as_deref(),
+            Some("add `wasm_target`: The target wasm target to build for. Defaults to \"wasm32-unknown-unknown\".")
+        );
+
+        let problems = validate_config_value(&json!({ "language": "rust", "type": "interface" }));
+        assert_eq!(
+            problems,
+            vec![ConfigProblem {
+                path: "interface".to_string(),
+                message: "missing required table [interface]".to_string(),
+                suggestion: Some(
+                    "add a [interface] table: Configuration for interface projects".to_string()
+                ),
+            }]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use tokio::time::Duration;
+use wasmcloud_control_interface::Client as CtlClient;
+
+use crate::{
+    common::boxed_err_to_anyhow,
+    config::DEFAULT_START_PROVIDER_TIMEOUT_MS,
+    wait::{
+        wait_for_provider_start_event, wait_for_provider_stop_event, FindEventOutcome,
+        ProviderStartedInfo, ProviderStoppedInfo,
+    },
+};
+
+/// Arguments required when starting a provider
+pub struct StartProviderArgs<'a> {
+    pub ctl_client: &'a CtlClient,
+    pub host_id: &'a str,
+    pub provider_ref: &'a str,
+    pub link_name: &'a str,
+    pub config_json: Option<String>,
+    pub skip_wait: bool,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Start a Wasmcloud provider. When `skip_wait` is set, the provider ID and contract ID of the returned
+/// information are empty, since they are only known once the provider has started
+pub async fn start_provider(
+    StartProviderArgs {
+        ctl_client,
+        host_id,
+        provider_ref,
+        link_name,
+        config_json,
+        skip_wait,
+        timeout_ms,
+    }: StartProviderArgs<'_>,
+) -> Result<ProviderStartedInfo> {
+    // If timeout isn't supplied, override with a longer timeout for starting provider
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_START_PROVIDER_TIMEOUT_MS);
+
+    // Create a receiver to use with the client
+    let mut receiver = ctl_client
+        .events_receiver()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("Failed to get lattice event channel")?;
+
+    // Start the provider
+    let ack = ctl_client
+        .start_provider(
+            host_id,
+            provider_ref,
+            Some(link_name.to_string()),
+            None,
+            config_json.clone(),
+        )
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .with_context(|| {
+            format!(
+                "Failed to start provider {} on host {:?} with link name {} and configuration {:?}",
+                provider_ref, host_id, link_name, config_json
+            )
+        })?;
+
+    if !ack.accepted {
+        bail!("Start provider ack not accepted: {}", ack.error);
+    }
+
+    // If skip_wait is specified, return incomplete information immediately
+    if skip_wait {
+        return Ok(ProviderStartedInfo {
+            host_id: host_id.into(),
+            provider_ref: provider_ref.into(),
+            provider_id: String::new(),
+            link_name: link_name.into(),
+            contract_id: String::new(),
+        });
+    }
+
+    // Wait for the provider to start
+    let event = wait_for_provider_start_event(
+        &mut receiver,
+        Duration::from_millis(timeout_ms),
+        host_id.into(),
+        provider_ref.into(),
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Timed out waiting for start event for provider [{}] on host [{}]",
+            provider_ref, host_id
+        )
+    })?;
+
+    match event {
+        FindEventOutcome::Success(info) => Ok(info),
+        FindEventOutcome::Failure(err) => Err(err).with_context(|| {
+            format!(
+                "Failed to start provider [{}] on host [{}]",
+                provider_ref, host_id
+            )
+        }),
+    }
+}
+
+/// Stop a provider
+#[allow(clippy::too_many_arguments)]
+pub async fn stop_provider(
+    client: &CtlClient,
+    host_id: &str,
+    provider_id: &str,
+    link_name: &str,
+    contract_id: &str,
+    annotations: Option<HashMap<String, String>>,
+    timeout_ms: u64,
+    skip_wait: bool,
+) -> Result<ProviderStoppedInfo> {
+    let mut receiver = client
+        .events_receiver()
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+
+    let ack = client
+        .stop_provider(host_id, provider_id, link_name, contract_id, annotations)
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+
+    if !ack.accepted {
+        bail!("Operation failed: {}", ack.error);
+    }
+
+    if skip_wait {
+        return Ok(ProviderStoppedInfo {
+            host_id: host_id.into(),
+            provider_id: provider_id.into(),
+            link_name: link_name.into(),
+            contract_id: contract_id.into(),
+        });
+    }
+
+    let event = wait_for_provider_stop_event(
+        &mut receiver,
+        Duration::from_millis(timeout_ms),
+        host_id.to_string(),
+        provider_id.to_string(),
+    )
+    .await?;
+
+    match event {
+        FindEventOutcome::Success(info) => Ok(info),
+        FindEventOutcome::Failure(err) => Err(err),
+    }
+}
@@ -23,6 +23,11 @@ const PROVIDER_ARCHIVE_CONFIG_MEDIA_TYPE: &str =
 const WASM_MEDIA_TYPE: &str = "application/vnd.module.wasm.content.layer.v1+wasm";
 const WASM_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasmcloud.actor.archive.config";
 const OCI_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar";
+const SBOM_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasmcloud.sbom.config";
+/// Media type of CycloneDX JSON SBOMs
+pub const CYCLONEDX_MEDIA_TYPE: &str = "application/vnd.cyclonedx+json";
+/// Media type of SPDX JSON SBOMs
+pub const SPDX_MEDIA_TYPE: &str = "application/spdx+json";
 
 // straight up stolen from oci_distribution::Reference
 pub const REFERENCE_REGEXP: &str = r"^((?:(?:[a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9])(?:(?:\.(?:[a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9]))+)?(?::[0-9]+)?/)?[a-z0-9]+(?:(?:(?:[._]|__|[-]*)[a-z0-9]+)+)?(?:(?:/[a-z0-9]+(?:(?:(?:[._]|__|[-]*)[a-z0-9]+)+)?)+)?)(?::([\w][\w.-]{0,127}))?(?:@([A-Za-z][A-Za-z0-9]*(?:[-_+.][A-Za-z][A-Za-z0-9]*)*[:][[:xdigit:]]{32,}))?$";
@@ -233,6 +238,76 @@ pub async fn push_oci_artifact(
     Ok(())
 }
 
+/// Attaches an SBOM to an artifact that was pushed to `url`, returning the reference the SBOM was pushed to.
+///
+/// Like `cosign attach sbom`, the SBOM is pushed to the artifact's repository as its own OCI artifact, tagged
+/// with the digest of the artifact's manifest: `sha256-<hex>.sbom`. The artifact itself is left unchanged, so
+/// clients that don't know about SBOMs can still pull it.
+pub async fn push_oci_sbom(
+    url: String,
+    sbom: impl AsRef<Path>,
+    options: OciPushOptions,
+) -> Result<String> {
+    let image: Reference = url.to_lowercase().parse()?;
+    let sbom_buf = tokio::fs::read(sbom.as_ref()).await?;
+    let document: serde_json::Value = serde_json::from_slice(&sbom_buf)
+        .map_err(|e| anyhow!("SBOM {} is not valid JSON: {e}", sbom.as_ref().display()))?;
+    let sbom_media_type = if document.get("bomFormat").and_then(|f| f.as_str()) == Some("CycloneDX")
+    {
+        CYCLONEDX_MEDIA_TYPE
+    } else if document.get("spdxVersion").is_some() {
+        SPDX_MEDIA_TYPE
+    } else {
+        bail!(
+            "SBOM {} is neither a CycloneDX nor an SPDX JSON document",
+            sbom.as_ref().display()
+        )
+    };
+
+    let mut client = Client::new(ClientConfig {
+        protocol: if options.insecure {
+            ClientProtocol::Http
+        } else {
+            ClientProtocol::Https
+        },
+        ..Default::default()
+    });
+
+    let auth = match (options.user, options.password) {
+        (Some(user), Some(password)) => RegistryAuth::Basic(user, password),
+        _ => RegistryAuth::Anonymous,
+    };
+
+    let digest = client.fetch_manifest_digest(&image, &auth).await?;
+    let sbom_image = Reference::with_tag(
+        image.registry().to_string(),
+        image.repository().to_string(),
+        format!("{}.sbom", digest.replace(':', "-")),
+    );
+
+    let config = Config {
+        data: b"{}".to_vec(),
+        media_type: SBOM_CONFIG_MEDIA_TYPE.to_string(),
+        annotations: None,
+    };
+    let layer = vec![ImageLayer {
+        data: sbom_buf,
+        media_type: sbom_media_type.to_string(),
+        annotations: None,
+    }];
+    let mut annotations = options.annotations.unwrap_or_default();
+    annotations.insert(
+        "org.opencontainers.image.description".to_string(),
+        format!("SBOM for {url}@{digest}"),
+    );
+    let manifest = OciImageManifest::build(&layer, &config, Some(annotations));
+
+    client
+        .push(&sbom_image, &layer, config, &auth, Some(manifest))
+        .await?;
+    Ok(sbom_image.whole())
+}
+
 /// Helper function to determine artifact type and validate that it is
 /// a supported artifact type
 pub async fn validate_artifact(artifact: &[u8]) -> Result<SupportedArtifacts> {
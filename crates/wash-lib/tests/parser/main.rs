@@ -1,9 +1,12 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 use claims::{assert_err, assert_ok};
 use semver::Version;
 use wash_lib::parser::{
-    get_config, ActorConfig, CommonConfig, LanguageConfig, RustConfig, TinyGoConfig, TypeConfig,
+    config_environments, config_schema, get_config, get_config_for_environment, validate_config,
+    ActorConfig, CommonConfig, CustomConfig, DevConfig, DevProviderConfig, DevTestConfig,
+    HooksConfig, LanguageConfig, OptimizeConfig, ProviderConfig, Revision, RustConfig, RustProfile,
+    TinyGoConfig, TinyGoProfile, TypeConfig,
 };
 
 #[test]
@@ -20,7 +23,9 @@ fn rust_actor() {
         config.language,
         LanguageConfig::Rust(RustConfig {
             cargo_path: Some("./cargo".into()),
-            target_path: Some("./target".into())
+            target_path: Some("./target".into()),
+            profile: None,
+            profiles: BTreeMap::new(),
         })
     );
 
@@ -33,7 +38,15 @@ fn rust_actor() {
             key_directory: PathBuf::from("./keys"),
             filename: Some("testactor.wasm".to_string()),
             wasm_target: "wasm32-unknown-unknown".to_string(),
-            call_alias: Some("testactor".to_string())
+            call_alias: Some("testactor".to_string()),
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
         })
     );
 
@@ -46,6 +59,8 @@ fn rust_actor() {
                 .canonicalize()
                 .unwrap(),
             wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
         }
     );
 }
@@ -62,7 +77,9 @@ fn tinygo_actor() {
     assert_eq!(
         config.language,
         LanguageConfig::TinyGo(TinyGoConfig {
-            tinygo_path: Some("path/to/tinygo".into())
+            tinygo_path: Some("path/to/tinygo".into()),
+            profile: None,
+            profiles: BTreeMap::new(),
         })
     );
 
@@ -75,7 +92,15 @@ fn tinygo_actor() {
             key_directory: PathBuf::from("./keys"),
             filename: Some("testactor.wasm".to_string()),
             wasm_target: "wasm32-unknown-unknown".to_string(),
-            call_alias: Some("testactor".to_string())
+            call_alias: Some("testactor".to_string()),
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
         })
     );
 
@@ -88,6 +113,263 @@ fn tinygo_actor() {
                 .canonicalize()
                 .unwrap(),
             wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
+        }
+    );
+}
+
+#[test]
+fn rust_wasi_component_actor() {
+    let result = get_config(
+        Some(PathBuf::from(
+            "./tests/parser/files/rust_wasi_component_actor.toml",
+        )),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Actor(ActorConfig {
+            claims: vec!["wasmcloud:httpserver".to_string()],
+            registry: None,
+            push_insecure: false,
+            key_directory: PathBuf::from("./keys"),
+            filename: None,
+            wasm_target: "wasm32-wasi".to_string(),
+            call_alias: None,
+            wasi_adapter_path: Some(PathBuf::from("./wasi_snapshot_preview1.wasm")),
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
+        })
+    );
+}
+
+#[test]
+/// When given a WASI adapter without a WASI target, should return an error.
+fn wasi_adapter_without_wasi_target() {
+    let result = get_config(
+        Some(PathBuf::from(
+            "./tests/parser/files/wasi_adapter_core_module.toml",
+        )),
+        None,
+    );
+
+    let err = assert_err!(result);
+    assert_eq!(
+        format!(
+            "wasi_adapter_path can only be used with a WASI wasm_target (wasm32-wasi or wasm32-wasip1), found wasm32-unknown-unknown in {}",
+            get_full_path("./tests/parser/files/wasi_adapter_core_module.toml")
+        ),
+        err.to_string().as_str()
+    );
+}
+
+#[test]
+fn custom_actor() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/custom_actor.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.language,
+        LanguageConfig::Custom(CustomConfig {
+            build_command: "make build".to_string(),
+            working_dir: PathBuf::from("./src"),
+            env: BTreeMap::from([("WASI_SDK_PATH".to_string(), "/opt/wasi-sdk".to_string())]),
+            output_path: PathBuf::from("out/testactor.wasm"),
+        })
+    );
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Actor(ActorConfig {
+            claims: vec!["wasmcloud:httpserver".to_string()],
+            registry: None,
+            push_insecure: false,
+            key_directory: PathBuf::from("./keys"),
+            filename: None,
+            wasm_target: "wasm32-wasi".to_string(),
+            call_alias: None,
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig {
+                strip: true,
+                wasm_opt: true,
+                wasm_opt_path: Some(PathBuf::from("/opt/binaryen/bin/wasm-opt")),
+                wasm_opt_args: vec!["-Oz".to_string()],
+                max_size: Some(1048576),
+            },
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
+        })
+    );
+
+    assert_eq!(
+        config.common,
+        CommonConfig {
+            name: "testactor".to_string(),
+            version: Version::parse("0.1.0").unwrap(),
+            path: PathBuf::from("./tests/parser/files/")
+                .canonicalize()
+                .unwrap(),
+            wasm_bin_name: None,
+            hooks: HooksConfig {
+                pre_build: vec!["./codegen.sh".to_string()],
+                post_build: vec![],
+                post_sign: vec![
+                    "test -s $WASH_ARTIFACT_PATH".to_string(),
+                    "echo signed".to_string()
+                ],
+            },
+            dev: DevConfig::default(),
+        }
+    );
+}
+
+#[test]
+/// When given a custom language config without a build command, should return an error.
+fn custom_actor_without_build_command() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/custom_no_command.toml")),
+        None,
+    );
+
+    let err = assert_err!(result);
+    assert_eq!(
+        format!(
+            "Missing build_command in custom language config in {}",
+            get_full_path("./tests/parser/files/custom_no_command.toml")
+        ),
+        err.to_string().as_str()
+    );
+}
+
+#[test]
+fn rust_profiles() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/rust_profiles.toml")),
+        None,
+    );
+
+    let mut config = assert_ok!(result);
+
+    let rust_config = match &config.language {
+        LanguageConfig::Rust(rust_config) => rust_config.clone(),
+        _ => panic!("expected rust language config"),
+    };
+    assert_eq!(rust_config.profile, Some("small".to_string()));
+    assert_eq!(
+        rust_config.build_profile(),
+        RustProfile {
+            cargo_profile: "release-small".to_string(),
+            features: vec![],
+            no_default_features: true,
+            extra_args: vec!["--locked".to_string()],
+        }
+    );
+
+    assert_ok!(config.language.select_profile("dev"));
+    match &config.language {
+        LanguageConfig::Rust(rust_config) => assert_eq!(
+            rust_config.build_profile(),
+            RustProfile {
+                cargo_profile: "dev".to_string(),
+                features: vec!["logging".to_string()],
+                no_default_features: false,
+                extra_args: vec![],
+            }
+        ),
+        _ => panic!("expected rust language config"),
+    }
+}
+
+#[test]
+fn tinygo_profiles() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/tinygo_profiles.toml")),
+        None,
+    );
+
+    let mut config = assert_ok!(result);
+
+    let build_profile = |language: &LanguageConfig| match language {
+        LanguageConfig::TinyGo(tinygo_config) => tinygo_config.build_profile(),
+        _ => panic!("expected tinygo language config"),
+    };
+
+    assert_eq!(
+        assert_ok!(build_profile(&config.language)),
+        TinyGoProfile {
+            gc: Some("leaking".to_string()),
+            scheduler: "none".to_string(),
+            no_debug: true,
+            extra_args: vec!["-opt".to_string(), "z".to_string()],
+        }
+    );
+
+    assert_ok!(config.language.select_profile("dev"));
+    assert_eq!(
+        assert_ok!(build_profile(&config.language)),
+        TinyGoProfile {
+            gc: None,
+            scheduler: "asyncify".to_string(),
+            no_debug: false,
+            extra_args: vec![],
+        }
+    );
+
+    assert_ok!(config.language.select_profile("missing"));
+    let err = assert_err!(build_profile(&config.language));
+    assert_eq!("Unknown tinygo build profile: missing", err.to_string());
+}
+
+#[test]
+fn rust_provider() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/rust_provider.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Provider(ProviderConfig {
+            capability_id: "wasmcloud:httpserver".to_string(),
+            vendor: "wasmcloud".to_string(),
+            targets: vec![
+                "x86_64-unknown-linux-gnu".to_string(),
+                "aarch64-apple-darwin".to_string()
+            ],
+            bin_name: Some("test-provider".to_string()),
+        })
+    );
+
+    assert_eq!(
+        config.common,
+        CommonConfig {
+            name: "testprovider".to_string(),
+            version: Version::parse("0.1.0").unwrap(),
+            path: PathBuf::from("./tests/parser/files/")
+                .canonicalize()
+                .unwrap(),
+            wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
         }
     );
 }
@@ -103,7 +385,9 @@ fn folder_path() {
         config.language,
         LanguageConfig::Rust(RustConfig {
             cargo_path: Some("./cargo".into()),
-            target_path: Some("./target".into())
+            target_path: Some("./target".into()),
+            profile: None,
+            profiles: BTreeMap::new(),
         })
     );
 }
@@ -245,6 +529,8 @@ fn minimal_rust_actor() {
         LanguageConfig::Rust(RustConfig {
             cargo_path: None,
             target_path: None,
+            profile: None,
+            profiles: BTreeMap::new(),
         })
     );
 
@@ -257,7 +543,15 @@ fn minimal_rust_actor() {
             key_directory: PathBuf::from("./keys"),
             filename: None,
             wasm_target: "wasm32-unknown-unknown".to_string(),
-            call_alias: None
+            call_alias: None,
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
         })
     );
 
@@ -270,6 +564,8 @@ fn minimal_rust_actor() {
                 .canonicalize()
                 .unwrap(),
             wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
         }
     )
 }
@@ -290,6 +586,8 @@ fn cargo_toml_actor() {
         LanguageConfig::Rust(RustConfig {
             cargo_path: None,
             target_path: None,
+            profile: None,
+            profiles: BTreeMap::new(),
         })
     );
 
@@ -302,7 +600,15 @@ fn cargo_toml_actor() {
             key_directory: PathBuf::from("./keys"),
             filename: None,
             wasm_target: "wasm32-unknown-unknown".to_string(),
-            call_alias: None
+            call_alias: None,
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
         })
     );
 
@@ -315,6 +621,273 @@ fn cargo_toml_actor() {
                 .canonicalize()
                 .unwrap(),
             wasm_bin_name: None,
+            hooks: HooksConfig::default(),
+            dev: DevConfig::default(),
         }
     )
 }
+
+#[test]
+/// Every valid config should pass validation, so the schema covers every key the parser accepts.
+fn valid_configs_have_no_problems() {
+    for file in [
+        "rust_actor.toml",
+        "rust_provider.toml",
+        "rust_profiles.toml",
+        "rust_wasi_component_actor.toml",
+        "minimal_rust_actor.toml",
+        "tinygo_actor.toml",
+        "tinygo_profiles.toml",
+        "custom_actor.toml",
+        "actor_claims.toml",
+        "dev_providers.toml",
+        "dev_test.toml",
+    ] {
+        let path = PathBuf::from("./tests/parser/files").join(file);
+        let problems = assert_ok!(validate_config(Some(path), None));
+        assert!(problems.is_empty(), "{file} has problems: {problems:?}");
+    }
+}
+
+#[test]
+/// When given an actor with the full set of claims options, it should parse all of them.
+fn actor_claims() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/actor_claims.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Actor(ActorConfig {
+            claims: vec![
+                "wasmcloud:httpserver".to_string(),
+                "wasmcloud:keyvalue".to_string()
+            ],
+            registry: None,
+            push_insecure: false,
+            key_directory: PathBuf::from("./keys"),
+            filename: None,
+            wasm_target: "wasm32-unknown-unknown".to_string(),
+            call_alias: None,
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: vec!["web".to_string(), "kv".to_string()],
+            rev: Some(Revision::Auto),
+            expires_in_days: Some(90),
+            not_before_days: Some(1),
+            issuer_key: Some("myorg_account".to_string()),
+            subject_key: Some("./keys/claimsactor_module.nk".to_string()),
+        })
+    );
+}
+
+#[test]
+/// When given a dev section, it should parse the providers with their defaults filled in.
+fn dev_providers() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/dev_providers.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.common.dev,
+        DevConfig {
+            providers: vec![
+                DevProviderConfig {
+                    image_ref: "wasmcloud.azurecr.io/httpserver:0.17.0".to_string(),
+                    contract_id: "wasmcloud:httpserver".to_string(),
+                    link_name: "default".to_string(),
+                    link_values: BTreeMap::from([(
+                        "address".to_string(),
+                        "0.0.0.0:8080".to_string()
+                    )]),
+                },
+                DevProviderConfig {
+                    image_ref: "file:///tmp/kvredis.par.gz".to_string(),
+                    contract_id: "wasmcloud:keyvalue".to_string(),
+                    link_name: "cache".to_string(),
+                    link_values: BTreeMap::new(),
+                },
+            ],
+            test: None,
+        }
+    );
+}
+
+#[test]
+fn dev_test() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/dev_test.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(
+        config.common.dev.test,
+        Some(DevTestConfig {
+            actor: Some("file:///tmp/devactor_tests_s.wasm".to_string()),
+            operation: "Testing.Start".to_string(),
+            payload: serde_json::json!({ "patterns": ["http_.*"], "options": {} }),
+            timeout_ms: 30000,
+        })
+    );
+}
+
+#[test]
+/// When given a revision that is neither a number nor "auto", should fail to parse.
+fn invalid_rev() {
+    let path = PathBuf::from("./tests/parser/files/invalid_rev.toml");
+    let problems = assert_ok!(validate_config(Some(path.clone()), None));
+    let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(paths, vec!["actor.rev"]);
+
+    assert_err!(get_config(Some(path), None));
+}
+
+#[test]
+/// When given an invalid config, should report every problem instead of only the first one.
+fn invalid_config_reports_every_problem() {
+    let path = PathBuf::from("./tests/parser/files/invalid_config.toml");
+    let problems = assert_ok!(validate_config(Some(path.clone()), None));
+    let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "hooks.pre_build",
+            "language",
+            "provider.capability_id",
+            "provider.vendr",
+            "version"
+        ]
+    );
+    assert_eq!(
+        problems[1].suggestion.as_deref(),
+        Some("did you mean `rust`?")
+    );
+
+    let err = assert_err!(get_config(Some(path), None));
+    assert!(err
+        .to_string()
+        .contains("provider.vendr: unknown key `vendr` (did you mean `vendor`?)"));
+}
+
+#[test]
+/// The schema published for editors should match the one used for validation.
+fn published_schema_is_up_to_date() {
+    let published: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string("../../docs/wasmcloud.toml.schema.json").unwrap())
+            .unwrap();
+    assert_eq!(
+        published,
+        config_schema(),
+        "regenerate it with `wash config schema --out docs/wasmcloud.toml.schema.json`"
+    );
+}
+
+#[test]
+fn environment_overlays_are_merged() {
+    let path = PathBuf::from("./tests/parser/files/environments.toml");
+
+    let environments = assert_ok!(config_environments(Some(path.clone())));
+    assert_eq!(environments, vec!["production", "staging"]);
+
+    let base = assert_ok!(get_config_for_environment(Some(path.clone()), None, None));
+    let actor = match base.project_type {
+        TypeConfig::Actor(actor) => actor,
+        _ => panic!("expected an actor config"),
+    };
+    assert_eq!(
+        actor.claims,
+        vec!["wasmcloud:httpserver", "wasmcloud:keyvalue"]
+    );
+    assert_eq!(actor.registry, Some("localhost:8080".to_string()));
+
+    let config = assert_ok!(get_config_for_environment(
+        Some(path.clone()),
+        None,
+        Some("production")
+    ));
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Actor(ActorConfig {
+            // Lists are replaced rather than appended to
+            claims: vec!["wasmcloud:httpserver".to_string()],
+            registry: Some("ghcr.io/example".to_string()),
+            push_insecure: false,
+            key_directory: PathBuf::from("./prod_keys"),
+            filename: None,
+            wasm_target: "wasm32-unknown-unknown".to_string(),
+            call_alias: Some("testactor".to_string()),
+            wasi_adapter_path: None,
+            optimize: OptimizeConfig::default(),
+            tags: Vec::new(),
+            rev: None,
+            expires_in_days: None,
+            not_before_days: None,
+            issuer_key: None,
+            subject_key: None,
+        })
+    );
+
+    let config = assert_ok!(get_config_for_environment(
+        Some(path.clone()),
+        None,
+        Some("staging")
+    ));
+    let actor = match config.project_type {
+        TypeConfig::Actor(actor) => actor,
+        _ => panic!("expected an actor config"),
+    };
+    assert_eq!(actor.registry, Some("staging.example.com".to_string()));
+    assert_eq!(actor.key_directory, PathBuf::from("./keys"));
+
+    let err = assert_err!(get_config_for_environment(
+        Some(path),
+        None,
+        Some("produciton")
+    ));
+    assert!(err
+        .to_string()
+        .starts_with("Unknown environment produciton, expected one of: production, staging"));
+
+    let problems = assert_ok!(validate_config(
+        Some(PathBuf::from("./tests/parser/files/environments.toml")),
+        Some("production")
+    ));
+    assert!(problems.is_empty(), "{problems:?}");
+}
+
+#[test]
+fn file_value_round_trips() {
+    for file in [
+        "rust_actor.toml",
+        "tinygo_profiles.toml",
+        "custom_actor.toml",
+        "dev_providers.toml",
+        "dev_test.toml",
+    ] {
+        let path = PathBuf::from("./tests/parser/files").join(file);
+        let config = assert_ok!(get_config(Some(path), None));
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("wasmcloud.toml"),
+            toml::to_string_pretty(&assert_ok!(config.to_file_value())).unwrap(),
+        )
+        .unwrap();
+        let written = assert_ok!(get_config(Some(dir.path().to_path_buf()), None));
+
+        assert_eq!(written.language, config.language, "{file}");
+        assert_eq!(written.project_type, config.project_type, "{file}");
+        assert_eq!(written.common.name, config.common.name, "{file}");
+        assert_eq!(written.common.version, config.common.version, "{file}");
+        assert_eq!(written.common.hooks, config.common.hooks, "{file}");
+        assert_eq!(written.common.dev, config.common.dev, "{file}");
+    }
+}
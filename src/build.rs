@@ -4,10 +4,18 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::Parser;
 use serde_json::json;
+use term_table::{
+    row::Row,
+    table_cell::{Alignment, TableCell},
+    Table,
+};
 
-use wash_lib::build::{build_project, SignConfig};
+use wash_lib::build::{
+    build_interface, build_project, build_workspace, write_sbom, BuildStatus, ProjectBuildResult,
+    SbomFormat, SectionSize, SignConfig, SizeReport,
+};
 use wash_lib::cli::CommandOutput;
-use wash_lib::parser::{get_config, TypeConfig};
+use wash_lib::parser::{get_config_for_environment, TypeConfig};
 
 /// Build (and sign) a wasmCloud actor, provider, or interface
 #[derive(Debug, Parser, Clone)]
@@ -46,13 +54,47 @@ pub(crate) struct BuildCommand {
     /// Skip signing the artifact and only use the native toolchain to build
     #[clap(long = "build-only")]
     pub build_only: bool,
+
+    /// Build every project in the workspace rooted at the config path (or current directory). Projects are listed
+    /// in a wasmcloud-workspace.toml file, taken from Cargo workspace members, or found by searching for wasmcloud.toml files
+    #[clap(long = "workspace")]
+    pub workspace: bool,
+
+    /// Name of the build profile to use, as configured in wasmcloud.toml. Defaults to the profile set in wasmcloud.toml, or "release"
+    #[clap(long = "profile", conflicts_with = "workspace")]
+    pub profile: Option<String>,
+
+    /// Name of the environment overlay from wasmcloud.toml to apply, e.g. "production". In a workspace, it is
+    /// applied to the projects that define it
+    #[clap(long = "env", env = "WASH_ENV")]
+    pub environment: Option<String>,
+
+    /// Write a software bill of materials for the built actor or provider next to the artifact, in the given
+    /// format (cyclonedx or spdx)
+    #[clap(long = "sbom", conflicts_with = "workspace")]
+    pub sbom: Option<SbomFormat>,
 }
 
-pub(crate) fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
-    let config = get_config(command.config_path, Some(true))?;
+pub(crate) async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
+    if command.workspace {
+        return handle_workspace_build(command).await;
+    }
+
+    let mut config = get_config_for_environment(
+        command.config_path,
+        Some(true),
+        command.environment.as_deref(),
+    )?;
+    if let Some(profile) = command.profile.as_deref() {
+        config.language.select_profile(profile)?;
+    }
 
     match config.project_type {
-        TypeConfig::Actor(ref _actor_config) => {
+        TypeConfig::Actor(_) | TypeConfig::Provider(_) => {
+            let (kind, path_key) = match config.project_type {
+                TypeConfig::Provider(_) => ("Provider", "provider_path"),
+                _ => ("Actor", "actor_path"),
+            };
             let sign_config = if command.build_only {
                 None
             } else {
@@ -64,29 +106,220 @@ pub(crate) fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                 })
             };
 
-            let actor_path = build_project(&config, sign_config)?;
+            let artifact = build_project(&config, sign_config).await?;
+            let artifact_path = &artifact.path;
+            let mut json_output = HashMap::from([
+                (path_key.to_string(), json!(artifact_path)),
+                ("signed".to_string(), json!(artifact.signing_keys.is_some())),
+                ("digest".to_string(), json!(artifact.digest)),
+                ("size".to_string(), json!(artifact.size)),
+                (
+                    "duration_secs".to_string(),
+                    json!(artifact.duration.as_secs_f64()),
+                ),
+            ]);
+            let mut text = if command.build_only {
+                format!("{kind} built and can be found at {artifact_path:?}")
+            } else {
+                format!("{kind} built and signed and can be found at {artifact_path:?}")
+            };
+            text.push_str(&format!(
+                "\n{} bytes, {} ({:.1}s)",
+                artifact.size,
+                artifact.digest,
+                artifact.duration.as_secs_f64()
+            ));
+            if let Some(format) = command.sbom {
+                let sbom_path = write_sbom(&config, &artifact, format).await?;
+                text.push_str(&format!("\nSBOM written to {sbom_path:?}"));
+                json_output.insert("sbom_path".to_string(), json!(sbom_path));
+            }
+            if let Some(keys) = artifact.signing_keys {
+                text.push_str(&format!(
+                    "\nIssuer: {}\nSubject: {}",
+                    keys.issuer, keys.subject
+                ));
+                json_output.insert("issuer".to_string(), json!(keys.issuer));
+                json_output.insert("subject".to_string(), json!(keys.subject));
+            }
+            if let Some(size_report) = artifact.size_report {
+                text.push_str(&format!(
+                    "\n\nOptimized from {} to {} bytes\n{}",
+                    size_report.before.total,
+                    size_report.after.total,
+                    size_report_table(&size_report)
+                ));
+                json_output.insert("size_report".to_string(), json!(size_report));
+            }
+            Ok(CommandOutput::new(text, json_output))
+        }
+        TypeConfig::Interface(ref interface_config) => {
+            let generated_files = build_interface(interface_config, &config.common).await?;
+            let html_path = config.common.path.join(&interface_config.html_target);
             let json_output = HashMap::from([
-                ("actor_path".to_string(), json!(actor_path)),
-                ("signed".to_string(), json!(command.build_only)),
+                ("html_path".to_string(), json!(html_path)),
+                ("generated_files".to_string(), json!(generated_files)),
             ]);
             Ok(CommandOutput::new(
-                if command.build_only {
-                    format!("Actor built and can be found at {actor_path:?}")
-                } else {
-                    format!("Actor built and signed and can be found at {actor_path:?}")
-                },
+                format!(
+                    "Interface built, documentation can be found at {html_path:?}. Generated files:\n{}",
+                    generated_files
+                        .iter()
+                        .map(|f| format!("  {}", f.display()))
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                ),
                 json_output,
             ))
         }
-        _ => {
-            // Until providers and interfaces have build support, this codepath won't be exercised
-            let path = build_project(&config, None)?;
-            Ok(CommandOutput::new(
-                format!("Built artifact can be found at {path:?}"),
-                HashMap::from([("path".to_string(), json!(path))]),
-            ))
+    }
+}
+
+async fn handle_workspace_build(command: BuildCommand) -> Result<CommandOutput> {
+    let root = command.config_path.unwrap_or_else(|| PathBuf::from("."));
+    let sign_config = if command.build_only {
+        None
+    } else {
+        Some(SignConfig {
+            keys_directory: command.keys_directory,
+            issuer: command.issuer,
+            subject: command.subject,
+            disable_keygen: command.disable_keygen,
+        })
+    };
+
+    let results = build_workspace(&root, sign_config, command.environment.as_deref()).await?;
+    let failed = results
+        .iter()
+        .filter(|r| r.status != BuildStatus::Built)
+        .count();
+
+    let mut text = workspace_summary_table(&results);
+    for result in results.iter().filter(|r| r.status == BuildStatus::Failed) {
+        text.push_str(&format!(
+            "\n{} failed to build: {}\n",
+            result.name,
+            result.error.clone().unwrap_or_default()
+        ));
+    }
+    text.push_str(&format!(
+        "\n{} of {} projects built successfully",
+        results.len() - failed,
+        results.len()
+    ));
+
+    let projects = results
+        .iter()
+        .map(|r| {
+            json!({
+                "name": r.name,
+                "path": r.path,
+                "status": r.status,
+                "artifact": r.artifact,
+                "duration_secs": r.duration.as_secs_f64(),
+                "error": r.error,
+            })
+        })
+        .collect::<Vec<_>>();
+    let json_output = HashMap::from([
+        ("projects".to_string(), json!(projects)),
+        ("failed".to_string(), json!(failed)),
+        // Exit with an error when any project wasn't built, while still reporting every project
+        ("success".to_string(), json!(failed == 0)),
+    ]);
+
+    Ok(CommandOutput::new(text, json_output))
+}
+
+/// Helper function to transform a SizeReport into a table string for printing
+fn size_report_table(report: &SizeReport) -> String {
+    let mut table = Table::new();
+    crate::util::configure_table_style(&mut table);
+
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Section", 1, Alignment::Left),
+        TableCell::new_with_alignment("Before", 1, Alignment::Right),
+        TableCell::new_with_alignment("After", 1, Alignment::Right),
+    ]));
+
+    let mut names: Vec<&str> = report
+        .before
+        .sections
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    for section in report.after.sections.iter() {
+        if !names.contains(&section.name.as_str()) {
+            names.push(&section.name);
         }
     }
+    let section_size = |sections: &[SectionSize], name: &str| {
+        sections
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.size.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for name in names {
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(name, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                section_size(&report.before.sections, name),
+                1,
+                Alignment::Right,
+            ),
+            TableCell::new_with_alignment(
+                section_size(&report.after.sections, name),
+                1,
+                Alignment::Right,
+            ),
+        ]))
+    }
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("total", 1, Alignment::Left),
+        TableCell::new_with_alignment(report.before.total, 1, Alignment::Right),
+        TableCell::new_with_alignment(report.after.total, 1, Alignment::Right),
+    ]));
+
+    table.render()
+}
+
+/// Helper function to transform workspace build results into a table string for printing
+fn workspace_summary_table(results: &[ProjectBuildResult]) -> String {
+    let mut table = Table::new();
+    crate::util::configure_table_style(&mut table);
+
+    table.add_row(Row::new(vec![
+        TableCell::new_with_alignment("Project", 1, Alignment::Left),
+        TableCell::new_with_alignment("Status", 1, Alignment::Left),
+        TableCell::new_with_alignment("Time", 1, Alignment::Left),
+        TableCell::new_with_alignment("Artifact", 1, Alignment::Left),
+    ]));
+    results.iter().for_each(|r| {
+        let status = match r.status {
+            BuildStatus::Built => "built",
+            BuildStatus::Failed => "failed",
+            BuildStatus::Skipped => "skipped",
+        };
+        // Skipped projects show why they were skipped, failures are listed in full below the table
+        let artifact = match (&r.artifact, r.status) {
+            (Some(artifact), _) => artifact.display().to_string(),
+            (None, BuildStatus::Skipped) => r.error.clone().unwrap_or_default(),
+            _ => String::new(),
+        };
+        table.add_row(Row::new(vec![
+            TableCell::new_with_alignment(r.name.clone(), 1, Alignment::Left),
+            TableCell::new_with_alignment(status, 1, Alignment::Left),
+            TableCell::new_with_alignment(
+                format!("{:.1}s", r.duration.as_secs_f64()),
+                1,
+                Alignment::Left,
+            ),
+            TableCell::new_with_alignment(artifact, 1, Alignment::Left),
+        ]))
+    });
+
+    table.render()
 }
 
 #[cfg(test)]
@@ -103,6 +336,10 @@ mod test {
         assert!(cmd.issuer.is_none());
         assert!(cmd.subject.is_none());
         assert!(cmd.keys_directory.is_none());
+        assert!(!cmd.workspace);
+        assert!(cmd.profile.is_none());
+        assert!(cmd.environment.is_none());
+        assert!(cmd.sbom.is_none());
 
         let cmd: BuildCommand = Parser::try_parse_from([
             "build",
@@ -115,6 +352,7 @@ mod test {
             "/tmp/sub.nk",
             "--keys-directory",
             "/tmp",
+            "--workspace",
         ])
         .unwrap();
         assert_eq!(cmd.config_path, Some(PathBuf::from("/")));
@@ -122,5 +360,29 @@ mod test {
         assert_eq!(cmd.issuer, Some("/tmp/iss.nk".to_string()));
         assert_eq!(cmd.subject, Some("/tmp/sub.nk".to_string()));
         assert_eq!(cmd.keys_directory, Some(PathBuf::from("/tmp")));
+        assert!(cmd.workspace);
+
+        let cmd: BuildCommand = Parser::try_parse_from([
+            "build",
+            "--profile",
+            "dev",
+            "--build-only",
+            "--env",
+            "staging",
+            "--sbom",
+            "spdx",
+        ])
+        .unwrap();
+        assert_eq!(cmd.profile, Some("dev".to_string()));
+        assert_eq!(cmd.environment, Some("staging".to_string()));
+        assert_eq!(cmd.sbom, Some(SbomFormat::Spdx));
+        assert!(Parser::try_parse_from(["build", "--sbom", "swid"])
+            .map(|_: BuildCommand| ())
+            .is_err());
+        assert!(
+            Parser::try_parse_from(["build", "--profile", "dev", "--workspace"])
+                .map(|_: BuildCommand| ())
+                .is_err()
+        );
     }
 }
@@ -227,6 +227,30 @@ pub(crate) fn call_output(
     ))
 }
 
+/// Creates a client for invoking actors through the NATS server at `rpc_host` and `rpc_port`, like `wash call`.
+/// Credentials that aren't given are taken from the default context. Invocations are signed with the cluster seed
+/// when it is given, and otherwise with the one of the default context
+pub(crate) async fn actor_rpc_client(
+    rpc_host: String,
+    rpc_port: String,
+    rpc_jwt: Option<String>,
+    rpc_seed: Option<String>,
+    rpc_credsfile: Option<PathBuf>,
+    cluster_seed: Option<ClusterSeed>,
+) -> Result<RpcClient> {
+    let opts = ConnectionOpts {
+        rpc_host: Some(rpc_host),
+        rpc_port: Some(rpc_port),
+        rpc_jwt,
+        rpc_seed,
+        rpc_credsfile,
+        lattice_prefix: None,
+        timeout_ms: default_timeout_ms(),
+        context: None,
+    };
+    Ok(rpc_client_from_opts(opts, cluster_seed).await?.0)
+}
+
 async fn rpc_client_from_opts(
     opts: ConnectionOpts,
     cmd_cluster_seed: Option<ClusterSeed>,
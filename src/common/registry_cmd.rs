@@ -16,8 +16,8 @@ use wash_lib::cli::{
     CommandOutput, OutputKind,
 };
 use wash_lib::registry::{
-    pull_oci_artifact, push_oci_artifact, validate_artifact, OciPullOptions, OciPushOptions,
-    SupportedArtifacts,
+    pull_oci_artifact, push_oci_artifact, push_oci_sbom, validate_artifact, OciPullOptions,
+    OciPushOptions, SupportedArtifacts,
 };
 
 use crate::appearance::spinner::Spinner;
@@ -127,22 +127,39 @@ pub(crate) async fn registry_push(
         OciPushOptions {
             config: cmd.config.map(PathBuf::from),
             allow_latest: cmd.allow_latest,
-            user: cmd.opts.user,
-            password: cmd.opts.password,
+            user: cmd.opts.user.clone(),
+            password: cmd.opts.password.clone(),
             insecure: cmd.opts.insecure,
-            annotations: Some(annotations),
+            annotations: Some(annotations.clone()),
         },
     )
     .await?;
 
-    spinner.finish_and_clear();
-
     let mut map = HashMap::new();
     map.insert("url".to_string(), json!(cmd.url));
-    Ok(CommandOutput::new(
-        format!("{SHOWER_EMOJI} Successfully validated and pushed to {artifact_url}"),
-        map,
-    ))
+    let mut text = format!("{SHOWER_EMOJI} Successfully validated and pushed to {artifact_url}");
+
+    if let Some(sbom) = cmd.sbom {
+        spinner.update_spinner_message(format!(" Attaching SBOM {} ...", sbom.display()));
+        let sbom_url = push_oci_sbom(
+            artifact_url,
+            &sbom,
+            OciPushOptions {
+                user: cmd.opts.user,
+                password: cmd.opts.password,
+                insecure: cmd.opts.insecure,
+                annotations: Some(annotations),
+                ..Default::default()
+            },
+        )
+        .await?;
+        text.push_str(&format!("\nAttached SBOM as {sbom_url}"));
+        map.insert("sbom_url".to_string(), json!(sbom_url));
+    }
+
+    spinner.finish_and_clear();
+
+    Ok(CommandOutput::new(text, map))
 }
 
 pub(crate) async fn handle_command(
@@ -166,6 +183,7 @@ pub(crate) async fn handle_command(
 mod tests {
     use crate::common::registry_cmd::{RegistryCommand, RegistryPullCommand, RegistryPushCommand};
     use clap::Parser;
+    use std::path::PathBuf;
 
     const ECHO_WASM: &str = "wasmcloud.azurecr.io/echo:0.2.0";
     const LOCAL_REGISTRY: &str = "localhost:5001";
@@ -318,6 +336,8 @@ mod tests {
             "supers3cr3t",
             "--user",
             "localuser",
+            "--sbom",
+            &format!("{TESTDIR}/logging.par.gz.cdx.json"),
         ])
         .unwrap();
         match push_all_options.reg {
@@ -327,8 +347,13 @@ mod tests {
                 opts,
                 allow_latest,
                 config,
+                sbom,
                 ..
             }) => {
+                assert_eq!(
+                    sbom,
+                    Some(PathBuf::from(format!("{TESTDIR}/logging.par.gz.cdx.json")))
+                );
                 assert_eq!(&url, logging_push_all_options);
                 assert_eq!(artifact, format!("{TESTDIR}/logging.par.gz"));
                 assert!(opts.insecure);
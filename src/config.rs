@@ -0,0 +1,146 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::json;
+use wash_lib::cli::CommandOutput;
+use wash_lib::parser::{config_schema, get_config_for_environment, validate_config};
+
+#[derive(Debug, Clone, Subcommand)]
+pub(crate) enum ConfigCliCommand {
+    /// Check a wasmcloud.toml file, reporting every problem found
+    #[clap(name = "validate")]
+    Validate {
+        /// Path to the wasmcloud.toml file or parent folder to validate
+        #[clap(short = 'p', long = "config-path")]
+        config_path: Option<PathBuf>,
+
+        /// Name of the environment overlay to validate the config with
+        #[clap(long = "env", env = "WASH_ENV")]
+        environment: Option<String>,
+    },
+    /// Print the effective config of a project, with defaults, the environment overlay, and WASMCLOUD_ environment
+    /// variables applied
+    #[clap(name = "show")]
+    Show {
+        /// Path to the wasmcloud.toml file or parent folder to resolve
+        #[clap(short = 'p', long = "config-path")]
+        config_path: Option<PathBuf>,
+
+        /// Name of the environment overlay from wasmcloud.toml to apply, e.g. "production"
+        #[clap(long = "env", env = "WASH_ENV")]
+        environment: Option<String>,
+    },
+    /// Print the JSON Schema for wasmcloud.toml files, for use with editors
+    #[clap(name = "schema")]
+    Schema {
+        /// Write the schema to this file instead of printing it
+        #[clap(long = "out")]
+        out: Option<PathBuf>,
+    },
+}
+
+pub(crate) fn handle_command(command: ConfigCliCommand) -> Result<CommandOutput> {
+    match command {
+        ConfigCliCommand::Validate {
+            config_path,
+            environment,
+        } => {
+            let problems = validate_config(config_path, environment.as_deref())?;
+            let text = if problems.is_empty() {
+                "wasmcloud.toml is valid".to_string()
+            } else {
+                format!(
+                    "Found {} problem(s) in wasmcloud.toml:\n{}",
+                    problems.len(),
+                    problems
+                        .iter()
+                        .map(|problem| format!("  - {problem}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            };
+            Ok(CommandOutput::new(
+                text,
+                HashMap::from([
+                    ("success".to_string(), json!(problems.is_empty())),
+                    ("problems".to_string(), json!(problems)),
+                ]),
+            ))
+        }
+        ConfigCliCommand::Show {
+            config_path,
+            environment,
+        } => {
+            let config =
+                get_config_for_environment(config_path, Some(true), environment.as_deref())?;
+            let value = config.to_file_value()?;
+            Ok(CommandOutput::new(
+                toml::to_string_pretty(&value)?,
+                HashMap::from([("config".to_string(), value)]),
+            ))
+        }
+        ConfigCliCommand::Schema { out } => {
+            let schema = config_schema();
+            match out {
+                Some(path) => {
+                    fs::write(
+                        &path,
+                        format!("{}\n", serde_json::to_string_pretty(&schema)?),
+                    )?;
+                    Ok(CommandOutput::new(
+                        format!("Wrote wasmcloud.toml schema to {}", path.display()),
+                        HashMap::from([("path".to_string(), json!(path))]),
+                    ))
+                }
+                None => Ok(CommandOutput::new(
+                    serde_json::to_string_pretty(&schema)?,
+                    HashMap::from([("schema".to_string(), schema)]),
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cmd {
+        #[clap(subcommand)]
+        config: ConfigCliCommand,
+    }
+
+    #[test]
+    fn test_config_comprehensive() {
+        let cmd: Cmd =
+            Parser::try_parse_from(["config", "validate", "-p", "/tmp/project"]).unwrap();
+        match cmd.config {
+            ConfigCliCommand::Validate { config_path, .. } => {
+                assert_eq!(config_path, Some(PathBuf::from("/tmp/project")))
+            }
+            _ => panic!("config constructed incorrect command"),
+        }
+
+        let cmd: Cmd = Parser::try_parse_from(["config", "show", "--env", "production"]).unwrap();
+        match cmd.config {
+            ConfigCliCommand::Show {
+                config_path,
+                environment,
+            } => {
+                assert!(config_path.is_none());
+                assert_eq!(environment, Some("production".to_string()));
+            }
+            _ => panic!("config constructed incorrect command"),
+        }
+
+        let cmd: Cmd =
+            Parser::try_parse_from(["config", "schema", "--out", "schema.json"]).unwrap();
+        match cmd.config {
+            ConfigCliCommand::Schema { out } => assert_eq!(out, Some(PathBuf::from("schema.json"))),
+            _ => panic!("config constructed incorrect command"),
+        }
+    }
+}
@@ -13,6 +13,9 @@ use wasmcloud_control_interface::{GetClaimsResponse, Host, HostInventory, LinkDe
 
 use crate::util::format_optional;
 
+/// Prefix of the labels the host sets on itself
+const HOSTCORE_LABEL_PREFIX: &str = "hostcore.";
+
 pub(crate) fn get_hosts_output(hosts: Vec<Host>) -> CommandOutput {
     let mut map = HashMap::new();
     map.insert("hosts".to_string(), json!(hosts));
@@ -94,17 +97,32 @@ pub(crate) fn hosts_table(hosts: Vec<Host>) -> String {
     table.add_row(Row::new(vec![
         TableCell::new_with_alignment("Host ID", 1, Alignment::Left),
         TableCell::new_with_alignment("Uptime (seconds)", 1, Alignment::Left),
+        TableCell::new_with_alignment("Labels", 1, Alignment::Left),
     ]));
     hosts.iter().for_each(|h| {
         table.add_row(Row::new(vec![
             TableCell::new_with_alignment(h.id.clone(), 1, Alignment::Left),
             TableCell::new_with_alignment(format!("{}", h.uptime_seconds), 1, Alignment::Left),
+            TableCell::new_with_alignment(host_labels(h), 1, Alignment::Left),
         ]))
     });
 
     table.render()
 }
 
+/// Formats the labels set on a host, leaving out the ones every host has (like its OS and architecture)
+fn host_labels(host: &Host) -> String {
+    let mut labels = host
+        .labels
+        .iter()
+        .flatten()
+        .filter(|(key, _)| !key.starts_with(HOSTCORE_LABEL_PREFIX))
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>();
+    labels.sort();
+    labels.join(", ")
+}
+
 /// Helper function to transform a HostInventory into a table string for printing
 pub(crate) fn host_inventory_table(inv: HostInventory) -> String {
     let mut table = Table::new();
@@ -0,0 +1,820 @@
+use std::str::FromStr;
+use std::{future::Future, path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use console::style;
+use notify::{event::EventKind, Event as NotifyEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+use tokio::{
+    select,
+    sync::{mpsc, watch},
+};
+use wash_lib::generate::emoji;
+use wash_lib::{
+    actor::{scale_actor, start_actor, StartActorArgs},
+    build::{build_project, discover_projects, SignConfig, BUILD_OUTPUT},
+    cli::dev::{
+        print_status, reload_artifact, restart_provider, start_dev_environment, status_printer,
+        ChangeFilter, DevEnvironment, DevTarget, LOG_PREFIX,
+    },
+    cli::CommandOutput,
+    config::downloads_dir,
+    id::{ClusterSeed, ModuleId, ServerId},
+    parser::{get_config_for_environment, ProjectConfig, TypeConfig},
+};
+use wasmbus_rpc::rpc_client::RpcClient;
+use wasmcloud_control_interface::{Client as CtlClient, Host};
+
+use self::view::{latest_host_log, SplitView};
+use crate::{
+    call::actor_rpc_client,
+    down::{handle_down, DownCommand},
+    up::{handle_up, NatsOpts, UpCommand, WadmOpts, WasmcloudOpts, DOWNLOADS_DIR},
+};
+
+mod view;
+
+/// Default time, in milliseconds, that file changes must settle for before `wash dev` rebuilds
+const DEFAULT_DEBOUNCE_MS: u64 = 300;
+
+#[derive(Debug, Clone, Parser)]
+pub struct DevCommand {
+    #[clap(flatten)]
+    pub(crate) nats_opts: NatsOpts,
+
+    #[clap(flatten)]
+    pub(crate) wasmcloud_opts: WasmcloudOpts,
+
+    #[clap(flatten)]
+    pub(crate) wadm_opts: WadmOpts,
+
+    /// ID of the host to use for `wash dev`
+    /// if one is not selected, `wash dev` will attempt to use the single host in the lattice
+    #[clap(long = "host-id", name = "host-id", value_parser)]
+    pub host_id: Option<ServerId>,
+
+    /// Path to code directory. Can be given several times to develop several projects on the same host at once
+    #[clap(
+        name = "code-dir",
+        long = "work-dir",
+        env = "WASH_DEV_CODE_DIR",
+        value_delimiter = ','
+    )]
+    pub code_dirs: Vec<PathBuf>,
+
+    /// Develop every project in the workspace rooted at the code directory (or current directory). Projects are
+    /// listed in a wasmcloud-workspace.toml file, taken from Cargo workspace members, or found by searching for
+    /// wasmcloud.toml files
+    #[clap(long = "workspace")]
+    pub workspace: bool,
+
+    /// Whether to leave the host running after dev
+    #[clap(
+        name = "leave-host-running",
+        long = "leave-host-running",
+        env = "WASH_DEV_LEAVE_HOST_RUNNING",
+        default_value = "false",
+        help = "Leave the wasmCloud host running after stopping the devloop"
+    )]
+    pub leave_host_running: bool,
+
+    /// Run the host in a subprocess (rather than detached mode)
+    #[clap(
+        name = "use-host-subprocess",
+        long = "use-host-subprocess",
+        env = "WASH_DEV_USE_HOST_SUBPROCESS",
+        default_value = "false",
+        help = "Run the wasmCloud host in a subprocess (rather than detached mode)"
+    )]
+    pub use_host_subprocess: bool,
+
+    /// Name of the environment overlay from wasmcloud.toml to apply, e.g. "local"
+    #[clap(long = "env", env = "WASH_ENV")]
+    pub environment: Option<String>,
+
+    /// Milliseconds to wait for file changes to settle before rebuilding. Changes made while a build is running
+    /// cancel it and start a new one
+    #[clap(
+        long = "debounce-ms",
+        env = "WASH_DEV_DEBOUNCE_MS",
+        default_value_t = DEFAULT_DEBOUNCE_MS
+    )]
+    pub debounce_ms: u64,
+
+    /// Link name of the provider when developing a capability provider. The provider running on the host with
+    /// this link name and the project's contract ID is replaced on every rebuild
+    #[clap(long = "link-name", default_value = "default")]
+    pub link_name: String,
+
+    /// Show the build status, the invocations to and from the actors under development and the host log in a
+    /// split view of the terminal. Press `p` to pause, `/` to filter, `1`-`3` to show or hide panes and `q` to quit
+    #[clap(
+        long = "split-view",
+        env = "WASH_DEV_SPLIT_VIEW",
+        conflicts_with = "use-host-subprocess"
+    )]
+    pub split_view: bool,
+}
+
+/// Utility struct for holding a wasmCloud host subprocess.
+/// This struct ensures that the join handle is aborted once the
+/// subprocess is dropped.
+struct HostSubprocess(Option<JoinHandle<()>>);
+
+impl HostSubprocess {
+    fn into_inner(mut self) -> Option<JoinHandle<()>> {
+        self.0.take()
+    }
+}
+
+impl Drop for HostSubprocess {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Handle `wash dev`
+pub async fn handle_command(
+    cmd: DevCommand,
+    output_kind: wash_lib::cli::OutputKind,
+) -> Result<CommandOutput> {
+    // Check if host is running
+    let pid_file = downloads_dir()?.join(DOWNLOADS_DIR);
+    let existing_instance = tokio::fs::metadata(pid_file).await.is_ok();
+
+    let mut host_subprocess: Option<HostSubprocess> = None;
+    // Test actors are invoked through the NATS server the host listens to for RPC
+    let rpc_host = cmd
+        .wasmcloud_opts
+        .rpc_host
+        .clone()
+        .unwrap_or_else(|| cmd.nats_opts.nats_host.clone());
+    let rpc_port = cmd
+        .wasmcloud_opts
+        .rpc_port
+        .unwrap_or(cmd.nats_opts.nats_port);
+    let cluster_seed = cmd
+        .wasmcloud_opts
+        .cluster_seed
+        .as_deref()
+        .map(ClusterSeed::from_str)
+        .transpose()?;
+    let rpc_jwt = cmd.wasmcloud_opts.rpc_jwt.clone();
+    let rpc_seed = cmd.wasmcloud_opts.rpc_seed.clone();
+    let rpc_credsfile = cmd.wasmcloud_opts.rpc_credsfile.clone();
+
+    // Start host if it's not already running
+    if !existing_instance {
+        eprintln!(
+            "{} {}{}",
+            emoji::WARN,
+            style("No running wasmcloud host detected (PID file missing), ").bold(),
+            style("starting a new host...").bold()
+        );
+        // Ensure that file loads are allowed
+        let mut wasmcloud_opts = cmd.wasmcloud_opts.clone();
+        wasmcloud_opts.allow_file_load = Some(true);
+
+        if cmd.use_host_subprocess {
+            // Use a subprocess
+            eprintln!(
+                "{} {}",
+                emoji::WRENCH,
+                style("starting wasmCloud host subprocess...").bold(),
+            );
+            let nats_opts = cmd.nats_opts.clone();
+            let wadm_opts = cmd.wadm_opts.clone();
+            host_subprocess = Some(HostSubprocess(Some(tokio::spawn(async move {
+                let _ = handle_up(
+                    UpCommand {
+                        detached: false,
+                        hosts: 1,
+                        nats_opts,
+                        wasmcloud_opts,
+                        wadm_opts,
+                        config: None,
+                        profile: None,
+                        print_config: false,
+                    },
+                    output_kind,
+                )
+                .await;
+                eprintln!(
+                    "{} {}",
+                    emoji::WRENCH,
+                    style("shutting down host subprocess...").bold(),
+                );
+            }))));
+
+            // Wait a while for wasmcloud to start up
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        } else {
+            // Run a detached process via running the equivalent of `wash up`
+
+            // Run wash up to start the host if not already running
+            let _ = handle_up(
+                UpCommand {
+                    detached: true,
+                    hosts: 1,
+                    nats_opts: cmd.nats_opts,
+                    wasmcloud_opts,
+                    wadm_opts: cmd.wadm_opts,
+                    config: None,
+                    profile: None,
+                    print_config: false,
+                },
+                output_kind,
+            )
+            .await?;
+        }
+
+        eprintln!(
+            "{} {}",
+            emoji::WRENCH,
+            style("Successfully started wasmCloud instance").bold(),
+        );
+    }
+
+    // Connect to the wasmcloud instance
+    let view_nats_client = if cmd.split_view {
+        Some(cmd.wasmcloud_opts.nats_client().await?)
+    } else {
+        None
+    };
+    let ctl_client = Arc::new(
+        cmd.wasmcloud_opts
+            .into_ctl_client(None)
+            .await
+            .context("failed to create wasmcloud control client")?,
+    );
+    let wait_ctl_client = ctl_client.clone();
+
+    // If we started our own instance, wait for one host to be present
+    if !existing_instance {
+        eprintln!("⏳ ");
+        eprintln!(
+            "{} {}",
+            emoji::HOURGLASS_DRAINING,
+            style("Waiting for host to become reachable...").bold(),
+        );
+
+        // Wait for up to a minute to find the host
+        let _ = timeout(
+            Duration::from_secs(60),
+            tokio::spawn(async move {
+                loop {
+                    match wait_ctl_client.get_hosts().await {
+                        Ok(hs) => match &hs[..] {
+                            [] => {}
+                            [h] => {
+                                eprintln!(
+                                    "{} {}",
+                                    emoji::GREEN_CHECK,
+                                    style(format!("Found single host w/ ID [{}]", h.id)).bold(),
+                                );
+                                break Ok(());
+                            }
+                            _hs => {
+                                bail!("Detected an unexpected number (>1) of hosts present.");
+                            }
+                        },
+                        Err(e) => {
+                            eprintln!(
+                                "{} {}",
+                                emoji::WARN,
+                                style(format!("Failed to get hosts (will retry in 5s): {e}"))
+                                    .bold(),
+                            );
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }),
+        )
+        .await
+        .context("wasmCloud host did not become reachable")?;
+    }
+
+    // Refresh host information (used in particular for existing instances)
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .or_else(|e| bail!("failed to retrieve hosts from lattice: {e}"))?;
+    let host: Host = match &hosts[..] {
+        [] => bail!("0 hosts detected, is wasmCloud running?"),
+        [h] => h.clone(),
+        _ => {
+            if let Some(host_id) = cmd.host_id.map(ServerId::into_string) {
+                hosts
+                    .into_iter()
+                    .find(|h| h.id == host_id)
+                    .ok_or_else(|| anyhow!("failed to find host [{host_id}]"))?
+            } else {
+                bail!(
+                    "{} hosts detected, please specify the host on which to deploy with --host-id",
+                    hosts.len()
+                )
+            }
+        }
+    };
+
+    // Resolve the configuration of every project to develop
+    let project_paths = dev_project_paths(&cmd.code_dirs, cmd.workspace)?;
+    let mut configs = Vec::with_capacity(project_paths.len());
+    for path in &project_paths {
+        configs.push(get_config_for_environment(
+            Some(path.clone()),
+            Some(true),
+            cmd.environment.as_deref(),
+        )?);
+    }
+    // Lines about a project are prefixed with its name when there are several
+    let prefix_width = configs
+        .iter()
+        .map(|config| config.common.name.len() + 2)
+        .max()
+        .unwrap_or_default();
+    let several_projects = configs.len() > 1;
+    let rpc_client = if configs
+        .iter()
+        .any(|config| config.common.dev.test.is_some())
+    {
+        Some(
+            actor_rpc_client(
+                rpc_host,
+                rpc_port.to_string(),
+                rpc_jwt,
+                rpc_seed,
+                rpc_credsfile,
+                cluster_seed,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    // Projects are built the same way as `wash build`
+    let sign_cfg: Option<SignConfig> = Some(SignConfig {
+        keys_directory: None,
+        issuer: None,
+        subject: None,
+        disable_keygen: false,
+    });
+    let host_id = ServerId::from_str(&host.id)?;
+    let debounce = Duration::from_millis(cmd.debounce_ms);
+
+    // The split view takes over the terminal from here on, showing the output of the builds in a pane
+    let mut view = match view_nats_client {
+        Some(nats_client) => Some(SplitView::open(
+            latest_host_log(&downloads_dir()?.join(DOWNLOADS_DIR)),
+            nats_client,
+        )?),
+        None => None,
+    };
+    let build_output = view.as_ref().map(SplitView::build_output);
+
+    // Start every project on the shared host, then watch each of them for changes in its own task
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let mut sessions = Vec::with_capacity(configs.len());
+    let started: Result<()> = async {
+        for (project_path, project_cfg) in project_paths.iter().zip(configs) {
+            let prefix = if several_projects {
+                let name = format!("[{}]", project_cfg.common.name);
+                format!("{} ", style(format!("{name:<prefix_width$}")).cyan())
+            } else {
+                String::new()
+            };
+            let nested = project_paths
+                .iter()
+                .filter(|path| *path != project_path && path.starts_with(project_path))
+                .cloned()
+                .collect();
+            let project = in_project_scope(
+                prefix.clone(),
+                build_output.clone(),
+                start_project(
+                    &ctl_client,
+                    &host,
+                    project_cfg,
+                    nested,
+                    sign_cfg.clone(),
+                    &cmd.link_name,
+                    rpc_client.clone(),
+                ),
+            )
+            .await?;
+            if let (Some(view), DevTarget::Actor { actor_id, .. }) = (&view, &project.target) {
+                view.spy(actor_id.as_ref(), &ctl_client).await;
+            }
+            sessions.push(tokio::spawn(in_project_scope(
+                prefix,
+                build_output.clone(),
+                watch_project(
+                    project,
+                    ctl_client.clone(),
+                    host_id.clone(),
+                    sign_cfg.clone(),
+                    debounce,
+                    stop_rx.clone(),
+                ),
+            )));
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(e) = started {
+        if let Some(view) = view {
+            view.close().await;
+        }
+        // The projects that already started are stopped the same way as when the dev loop is interrupted
+        eprintln!("🛑 stopping devloop...");
+        if let Err(stop_error) = stop_dev_loop(
+            stop_tx,
+            sessions,
+            &ctl_client,
+            &host_id,
+            host_subprocess,
+            cmd.leave_host_running,
+            output_kind,
+        )
+        .await
+        {
+            eprintln!("{} failed to stop devloop: {stop_error:#}", emoji::WARN);
+        }
+        return Err(e);
+    }
+
+    // Run until Ctrl + c is pressed, or the split view is quit
+    let signal = match view.as_mut() {
+        Some(view) => select! {
+            signal = tokio::signal::ctrl_c() => signal,
+            _ = view.closed() => Ok(()),
+        },
+        None => tokio::signal::ctrl_c().await,
+    };
+    if let Some(view) = view {
+        view.close().await;
+    }
+    signal.context("failed to wait for ctrl_c signal")?;
+    eprintln!("🛑 stopping devloop...");
+    stop_dev_loop(
+        stop_tx,
+        sessions,
+        &ctl_client,
+        &host_id,
+        host_subprocess,
+        cmd.leave_host_running,
+        output_kind,
+    )
+    .await?;
+
+    Ok(CommandOutput::default())
+}
+
+/// Stops watching the projects and, unless the host is left running, tears down their dev providers and links
+/// and stops the host
+async fn stop_dev_loop(
+    stop_tx: watch::Sender<bool>,
+    sessions: Vec<JoinHandle<DevEnvironment>>,
+    ctl_client: &CtlClient,
+    host_id: &ServerId,
+    host_subprocess: Option<HostSubprocess>,
+    leave_host_running: bool,
+    output_kind: wash_lib::cli::OutputKind,
+) -> Result<()> {
+    let _ = stop_tx.send(true);
+    let mut environments = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        environments.push(session.await?);
+    }
+
+    if !leave_host_running {
+        for dev_environment in environments {
+            if !dev_environment.is_empty() {
+                eprintln!("⏳ tearing down dev providers and links...");
+                dev_environment.teardown(ctl_client, host_id).await;
+            }
+        }
+        eprintln!("⏳ stopping wasmCloud instance...");
+        handle_down(DownCommand::default(), output_kind).await?;
+        if let Some(handle) = host_subprocess.and_then(|hs| hs.into_inner()) {
+            handle.await?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs a future of a project with its log prefix, sending its build output to the split view when it is open
+async fn in_project_scope<F: Future>(
+    prefix: String,
+    build_output: Option<mpsc::UnboundedSender<String>>,
+    future: F,
+) -> F::Output {
+    match build_output {
+        Some(output) => {
+            LOG_PREFIX
+                .scope(prefix, BUILD_OUTPUT.scope(output, future))
+                .await
+        }
+        None => LOG_PREFIX.scope(prefix, future).await,
+    }
+}
+
+/// Returns the canonical paths of the projects to develop: the given code directories (or the current directory),
+/// or every project in the workspaces rooted at them
+fn dev_project_paths(code_dirs: &[PathBuf], workspace: bool) -> Result<Vec<PathBuf>> {
+    let dirs = if code_dirs.is_empty() {
+        vec![std::env::current_dir()?]
+    } else {
+        code_dirs.to_vec()
+    };
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        if workspace {
+            paths.extend(discover_projects(&dir)?);
+        } else {
+            paths.push(dir.canonicalize().with_context(|| {
+                format!("project directory [{}] does not exist", dir.display())
+            })?);
+        }
+    }
+    if paths.is_empty() {
+        bail!("no projects found to develop");
+    }
+    Ok(paths)
+}
+
+/// A project running on the dev host, with the watcher of its files
+struct DevProject {
+    config: ProjectConfig,
+    target: DevTarget,
+    environment: DevEnvironment,
+    /// Kept so the project keeps being watched
+    _watcher: RecommendedWatcher,
+    /// Paths of the changes that should trigger a rebuild
+    changes: mpsc::UnboundedReceiver<PathBuf>,
+}
+
+/// Build a project and start it on the dev host, along with the providers and links from its dev section, then
+/// start watching its files. Changes in the `nested` project directories are left to those projects.
+async fn start_project(
+    ctl_client: &CtlClient,
+    host: &Host,
+    project_cfg: ProjectConfig,
+    nested: Vec<PathBuf>,
+    sign_cfg: Option<SignConfig>,
+    link_name: &str,
+    rpc_client: Option<RpcClient>,
+) -> Result<DevProject> {
+    print_status(format!(
+        "{} {}",
+        emoji::CONSTRUCTION_BARRIER,
+        style("Starting project build").bold()
+    ));
+
+    // Build the project
+    let artifact_path = build_project(&project_cfg, sign_cfg)
+        .await?
+        .path
+        .canonicalize()?;
+    print_status(format!(
+        "✅ successfully built project at [{}]",
+        artifact_path.display()
+    ));
+
+    // Since we're using the artifact from file on disk, the ref should be the file path (canonicalized) on disk as URI
+    let artifact_ref = format!("file://{}", artifact_path.display());
+    let host_id = ServerId::from_str(&host.id)?;
+    let target = match &project_cfg.project_type {
+        TypeConfig::Provider(provider_config) => {
+            restart_provider(
+                ctl_client,
+                &host_id,
+                &artifact_ref,
+                link_name,
+                &provider_config.capability_id,
+            )
+            .await?;
+            DevTarget::Provider {
+                link_name: link_name.to_string(),
+            }
+        }
+        _ => DevTarget::Actor {
+            actor_id: ModuleId::from_str(&start_dev_actor(ctl_client, host, &artifact_ref).await?)?,
+            actor_ref: artifact_ref,
+        },
+    };
+
+    // Start the providers from the dev section of wasmcloud.toml, linked to the actor under development, and run
+    // its tests a first time
+    let dev_actor_id = match &target {
+        DevTarget::Actor { actor_id, .. } => Some(actor_id),
+        DevTarget::Provider { .. } => None,
+    };
+    let environment = start_dev_environment(
+        ctl_client,
+        &host_id,
+        &project_cfg.common.dev,
+        dev_actor_id,
+        rpc_client,
+    )
+    .await?;
+    environment.run_tests().await;
+
+    // Spawn a file watcher that sends the paths of changes that should trigger a rebuild on change_tx. Build
+    // outputs and ignored files are filtered out, so the watcher keeps running during builds.
+    let mut filter = ChangeFilter::new(&project_cfg.common.path)?;
+    for path in &nested {
+        filter.exclude(path);
+    }
+    let watch_path = filter.project_path().to_path_buf();
+    let (change_tx, changes) = mpsc::unbounded_channel::<PathBuf>();
+    // The watcher calls back from its own thread, outside of the project's task
+    let print_watch_status = status_printer();
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<NotifyEvent>| match res {
+            Ok(event) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    for path in event.paths {
+                        if filter.is_change(&path) {
+                            let _ = change_tx.send(path);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                print_watch_status(&format!("[error] watch failed: {e:?}"));
+            }
+        })?;
+    watcher.watch(&watch_path, RecursiveMode::Recursive)?;
+
+    Ok(DevProject {
+        config: project_cfg,
+        target,
+        environment,
+        _watcher: watcher,
+        changes,
+    })
+}
+
+/// Rebuild and reload a project whenever its files change, until `stop` is set. Failed builds and reloads are
+/// reported and the project keeps being watched. Returns the providers and links that were set up for the
+/// project, so they can be torn down.
+async fn watch_project(
+    project: DevProject,
+    ctl_client: Arc<CtlClient>,
+    host_id: ServerId,
+    sign_cfg: Option<SignConfig>,
+    debounce: Duration,
+    mut stop: watch::Receiver<bool>,
+) -> DevEnvironment {
+    let DevProject {
+        config: project_cfg,
+        mut target,
+        environment,
+        _watcher,
+        mut changes,
+    } = project;
+
+    // Watch FS for changes and listen for Ctrl + C in tandem
+    print_status("👀 watching for file changes (press Ctrl+c to stop)...");
+    'dev: loop {
+        select! {
+            Some(_) = changes.recv() => {},
+            _ = stop.changed() => break,
+        }
+        wait_for_quiet(&mut changes, debounce).await;
+
+        // Build the project, starting over whenever files change again before the build is done
+        let built = loop {
+            print_status(format!(
+                "{} {}",
+                emoji::CONSTRUCTION_BARRIER,
+                style("Starting project build").bold()
+            ));
+            select! {
+                built = build_project(&project_cfg, sign_cfg.clone()) => break built,
+                Some(_) = changes.recv() => {
+                    print_status(format!("{} {}", emoji::RECYCLE,
+                        style("files changed, cancelling build...").bold()));
+                    wait_for_quiet(&mut changes, debounce).await;
+                },
+                _ = stop.changed() => break 'dev,
+            }
+        };
+        let reloaded = match built {
+            Ok(artifact) => {
+                reload_artifact(
+                    &project_cfg,
+                    &artifact.path,
+                    &mut target,
+                    host_id.clone(),
+                    &ctl_client,
+                )
+                .await
+            }
+            Err(e) => Err(e.context("build failed")),
+        };
+        match reloaded {
+            Ok(_) => environment.run_tests().await,
+            Err(e) => print_status(format!(
+                "{} {}",
+                emoji::ERROR,
+                style(format!("{e:#}")).bold()
+            )),
+        }
+        print_status("👀 watching for file changes (press Ctrl+c to stop)...");
+    }
+
+    environment
+}
+
+/// Waits until no changes have arrived for the debounce window, so a burst of changes triggers a single build
+async fn wait_for_quiet(changes: &mut mpsc::UnboundedReceiver<PathBuf>, debounce: Duration) {
+    while let Ok(Some(_)) = timeout(debounce, changes.recv()).await {}
+}
+
+/// Attempt to find or create the actor on the dev host, scaling it back to one instance if it already exists,
+/// and return its ID
+async fn start_dev_actor(ctl_client: &CtlClient, host: &Host, actor_ref: &str) -> Result<String> {
+    let inventory = ctl_client.get_host_inventory(&host.id).await.or_else(|e| {
+        bail!(
+            "failed to retrieve host inventory for host [{}]: {e}",
+            &host.id
+        )
+    })?;
+    if let Some(existing_actor) = inventory
+        .actors
+        .into_iter()
+        .find(|a| a.image_ref.as_deref() == Some(actor_ref))
+    {
+        scale_actor(ctl_client, &host.id, actor_ref, &existing_actor.id, 1, None).await?;
+        Ok(existing_actor.id)
+    } else {
+        // Start the actor for the first time
+        start_actor(StartActorArgs {
+            ctl_client,
+            host_id: &host.id,
+            actor_ref,
+            count: 1,
+            skip_wait: false,
+            timeout_ms: None,
+        })
+        .await?
+        .actor_id
+        .ok_or_else(|| anyhow!("failed to do thing"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dev_comprehensive() {
+        let cmd: DevCommand = Parser::try_parse_from([
+            "dev",
+            "--work-dir",
+            "./actor",
+            "--work-dir",
+            "./provider,./other",
+            "--workspace",
+            "--debounce-ms",
+            "500",
+            "--split-view",
+        ])
+        .unwrap();
+        assert_eq!(
+            cmd.code_dirs,
+            vec![
+                PathBuf::from("./actor"),
+                PathBuf::from("./provider"),
+                PathBuf::from("./other")
+            ]
+        );
+        assert!(cmd.workspace);
+        assert_eq!(cmd.debounce_ms, 500);
+        assert_eq!(cmd.link_name, "default");
+        assert!(cmd.split_view);
+
+        let cmd: DevCommand = Parser::try_parse_from(["dev"]).unwrap();
+        assert!(cmd.code_dirs.is_empty());
+        assert!(!cmd.workspace);
+        assert_eq!(cmd.debounce_ms, DEFAULT_DEBOUNCE_MS);
+        assert!(!cmd.split_view);
+
+        // The host subprocess writes its log to the terminal, which the split view takes over
+        assert!(
+            DevCommand::try_parse_from(["dev", "--split-view", "--use-host-subprocess"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! Split view of `wash dev`, showing the build status, the invocations to and from the actors under development
+//! and the log of the host in panes of the terminal
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Result};
+use console::{pad_str, strip_ansi_codes, style, truncate_str, Alignment, Key, Term};
+use futures::StreamExt;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+    select,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+    time::{interval, Duration},
+};
+use wash_lib::spier::{ObservedInvocation, Spier};
+use wasmcloud_control_interface::Client as CtlClient;
+
+/// Number of lines kept for each pane
+const MAX_PANE_LINES: usize = 2000;
+/// How often the view is redrawn, if anything changed, and the host log is checked for new lines
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+/// How much of the end of the host log is shown when the view opens
+const HOST_LOG_TAIL_BYTES: u64 = 16 * 1024;
+
+/// A pane of the split view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Build,
+    Invocations,
+    HostLog,
+}
+
+impl Pane {
+    const ALL: [Pane; 3] = [Pane::Build, Pane::Invocations, Pane::HostLog];
+
+    fn title(self) -> &'static str {
+        match self {
+            Pane::Build => "Build",
+            Pane::Invocations => "Invocations",
+            Pane::HostLog => "Host log",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Pane::Build => 0,
+            Pane::Invocations => 1,
+            Pane::HostLog => 2,
+        }
+    }
+}
+
+/// What the keyboard asks the view to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    TogglePause,
+    TogglePane(Pane),
+    /// Show the filter being typed, which isn't applied yet
+    EditFilter(String),
+    /// Only show the lines containing the text, or every line if it is empty
+    SetFilter(String),
+    Quit,
+}
+
+/// Turns key presses into commands for the view. Keys are typed into the filter while it is being edited.
+#[derive(Debug, Default)]
+struct KeyMap {
+    editing: Option<String>,
+}
+
+impl KeyMap {
+    fn command(&mut self, key: Key) -> Option<Command> {
+        if let Some(filter) = self.editing.as_mut() {
+            return match key {
+                Key::Enter => self.editing.take().map(Command::SetFilter),
+                Key::Escape => {
+                    self.editing = None;
+                    Some(Command::SetFilter(String::new()))
+                }
+                Key::Backspace => {
+                    filter.pop();
+                    Some(Command::EditFilter(filter.clone()))
+                }
+                Key::Char(c) if !c.is_control() => {
+                    filter.push(c);
+                    Some(Command::EditFilter(filter.clone()))
+                }
+                _ => None,
+            };
+        }
+
+        match key {
+            Key::Char('p') | Key::Char(' ') => Some(Command::TogglePause),
+            Key::Char('1') => Some(Command::TogglePane(Pane::Build)),
+            Key::Char('2') => Some(Command::TogglePane(Pane::Invocations)),
+            Key::Char('3') => Some(Command::TogglePane(Pane::HostLog)),
+            Key::Char('/') => {
+                self.editing = Some(String::new());
+                Some(Command::EditFilter(String::new()))
+            }
+            Key::Escape => Some(Command::SetFilter(String::new())),
+            Key::Char('q') => Some(Command::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// What is shown in the view
+#[derive(Debug, Default)]
+struct ViewState {
+    panes: [VecDeque<String>; 3],
+    hidden: [bool; 3],
+    /// Lines received while the view is paused, added to the panes once it is resumed
+    held: Vec<(Pane, String)>,
+    paused: bool,
+    filter: String,
+    /// The filter being typed, if any
+    editing: Option<String>,
+}
+
+impl ViewState {
+    fn push(&mut self, pane: Pane, line: &str) {
+        // Tabs and carriage returns would break the layout
+        let line = line.replace('\t', "    ").replace('\r', "");
+        if self.paused {
+            if self.held.len() >= MAX_PANE_LINES * Pane::ALL.len() {
+                self.held.remove(0);
+            }
+            self.held.push((pane, line));
+            return;
+        }
+        let lines = &mut self.panes[pane.index()];
+        if lines.len() >= MAX_PANE_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::TogglePause => {
+                self.paused = !self.paused;
+                if !self.paused {
+                    for (pane, line) in std::mem::take(&mut self.held) {
+                        self.push(pane, &line);
+                    }
+                }
+            }
+            Command::TogglePane(pane) => self.hidden[pane.index()] = !self.hidden[pane.index()],
+            Command::EditFilter(filter) => self.editing = Some(filter),
+            Command::SetFilter(filter) => {
+                self.editing = None;
+                self.filter = filter;
+            }
+            Command::Quit => {}
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        self.filter.is_empty()
+            || strip_ansi_codes(line)
+                .to_lowercase()
+                .contains(&self.filter.to_lowercase())
+    }
+
+    /// The last lines of a pane that match the filter, oldest first
+    fn visible_lines(&self, pane: Pane, count: usize) -> Vec<&str> {
+        let mut lines: Vec<&str> = self.panes[pane.index()]
+            .iter()
+            .rev()
+            .filter(|line| self.matches(line))
+            .take(count)
+            .map(String::as_str)
+            .collect();
+        lines.reverse();
+        lines
+    }
+
+    /// Renders the view as the rows of a terminal of the given size
+    fn render(&self, rows: usize, cols: usize) -> Vec<String> {
+        let panes: Vec<Pane> = Pane::ALL
+            .into_iter()
+            .filter(|pane| !self.hidden[pane.index()])
+            .collect();
+        let available = rows.saturating_sub(1);
+        let mut frame = Vec::with_capacity(rows);
+
+        for (i, pane) in panes.iter().enumerate() {
+            // Rows that don't divide evenly go to the first panes
+            let height = available / panes.len() + usize::from(i < available % panes.len());
+            if height == 0 {
+                continue;
+            }
+            let mut title = format!(" [{}] {}", pane.index() + 1, pane.title());
+            if !self.filter.is_empty() {
+                let _ = write!(title, " (filter: {})", self.filter);
+            }
+            frame.push(style(fit(&title, cols)).reverse().to_string());
+            let lines = self.visible_lines(*pane, height - 1);
+            frame.extend(lines.iter().map(|line| fit(line, cols)));
+            frame.extend((lines.len()..height - 1).map(|_| fit("", cols)));
+        }
+        while frame.len() < available {
+            frame.push(fit("", cols));
+        }
+
+        let status = match (&self.editing, self.paused) {
+            (Some(filter), _) => format!(" filter: {filter}_   (enter: apply, esc: clear)"),
+            (None, true) => format!(
+                " PAUSED, {} new lines   p: resume  /: filter  1-3: show/hide panes  q: quit",
+                self.held.len()
+            ),
+            (None, false) => " p: pause  /: filter  1-3: show/hide panes  q: quit".to_string(),
+        };
+        frame.push(style(fit(&status, cols)).bold().to_string());
+        frame
+    }
+}
+
+/// Truncates or pads a line to exactly fill a row of the terminal
+fn fit(line: &str, cols: usize) -> String {
+    pad_str(&truncate_str(line, cols, "…"), cols, Alignment::Left, None).into_owned()
+}
+
+/// Handle on the split view, which takes over the terminal until it is closed
+pub(crate) struct SplitView {
+    build_output: mpsc::UnboundedSender<String>,
+    invocations: mpsc::UnboundedSender<String>,
+    nats_client: async_nats::Client,
+    quit: oneshot::Receiver<()>,
+    close: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl SplitView {
+    /// Takes over the terminal and starts drawing the view, tailing the given host log if there is one. Spied
+    /// invocations are received with `nats_client`.
+    pub(crate) fn open(host_log: Option<PathBuf>, nats_client: async_nats::Client) -> Result<Self> {
+        let term = Term::stderr();
+        if !term.is_term() {
+            bail!("the split view of `wash dev` needs a terminal");
+        }
+
+        let (build_output, build_rx) = mpsc::unbounded_channel();
+        let (invocations, invocation_rx) = mpsc::unbounded_channel();
+        let (quit_tx, quit) = oneshot::channel();
+        let (close, close_rx) = oneshot::channel();
+        let commands = read_commands();
+        let task = tokio::spawn(run(
+            term,
+            host_log,
+            build_rx,
+            invocation_rx,
+            commands,
+            quit_tx,
+            close_rx,
+        ));
+
+        Ok(SplitView {
+            build_output,
+            invocations,
+            nats_client,
+            quit,
+            close,
+            task,
+        })
+    }
+
+    /// Returns the sender of the lines shown in the build pane
+    pub(crate) fn build_output(&self) -> mpsc::UnboundedSender<String> {
+        self.build_output.clone()
+    }
+
+    /// Shows the invocations to and from an actor and its linked providers in the invocations pane
+    pub(crate) async fn spy(&self, actor_id: &str, ctl_client: &CtlClient) {
+        let mut spier = match Spier::new(actor_id, ctl_client, &self.nats_client).await {
+            Ok(spier) => spier,
+            Err(e) => {
+                let _ = self
+                    .invocations
+                    .send(format!("failed to spy on actor [{actor_id}]: {e:#}"));
+                return;
+            }
+        };
+        let _ = self.invocations.send(format!(
+            "spying on invocations of actor [{}]",
+            spier.actor_id()
+        ));
+        let invocations = self.invocations.clone();
+        tokio::spawn(async move {
+            while let Some(invocation) = spier.next().await {
+                if invocations.send(format_invocation(&invocation)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Resolves once the view is closed from the keyboard
+    pub(crate) async fn closed(&mut self) {
+        let _ = (&mut self.quit).await;
+    }
+
+    /// Stops drawing the view and gives the terminal back
+    pub(crate) async fn close(self) {
+        let _ = self.close.send(());
+        let _ = self.task.await;
+    }
+}
+
+/// Formats an invocation as a single line
+fn format_invocation(invocation: &ObservedInvocation) -> String {
+    let message = invocation.message.to_string();
+    format!(
+        "{} {} → {} {} {}",
+        invocation.timestamp.format("%H:%M:%S%.3f"),
+        style(&invocation.from).cyan(),
+        style(&invocation.to).cyan(),
+        style(&invocation.invocation.operation).bold(),
+        message.split_whitespace().collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// Reads keys from the terminal in a thread of its own, turning them into commands. The thread stops when the
+/// view is quit or the terminal is interrupted with Ctrl+c, which raises SIGINT as usual.
+fn read_commands() -> mpsc::UnboundedReceiver<Command> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let term = Term::stderr();
+        let mut keys = KeyMap::default();
+        while let Ok(key) = term.read_key() {
+            if let Some(command) = keys.command(key) {
+                let quit = command == Command::Quit;
+                if tx.send(command).is_err() || quit {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Draws the view until it is closed
+async fn run(
+    term: Term,
+    host_log: Option<PathBuf>,
+    mut build_rx: mpsc::UnboundedReceiver<String>,
+    mut invocation_rx: mpsc::UnboundedReceiver<String>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    quit: oneshot::Sender<()>,
+    mut close: oneshot::Receiver<()>,
+) {
+    let mut state = ViewState::default();
+    let mut host_log = match host_log {
+        Some(path) => Some(LogTail::new(path)),
+        None => {
+            state.push(Pane::HostLog, "no host log found");
+            None
+        }
+    };
+    let mut quit = Some(quit);
+    let mut size = (0, 0);
+    let mut dirty = true;
+    let mut refresh = interval(REFRESH_INTERVAL);
+
+    let _ = term.hide_cursor();
+    let _ = term.clear_screen();
+    loop {
+        select! {
+            Some(line) = build_rx.recv() => {
+                state.push(Pane::Build, &line);
+                dirty = true;
+            }
+            Some(line) = invocation_rx.recv() => {
+                state.push(Pane::Invocations, &line);
+                dirty = true;
+            }
+            Some(command) = commands.recv() => {
+                if command == Command::Quit {
+                    if let Some(quit) = quit.take() {
+                        let _ = quit.send(());
+                    }
+                }
+                state.apply(command);
+                dirty = true;
+            }
+            _ = refresh.tick() => {
+                if let Some(tail) = host_log.as_mut() {
+                    for line in tail.read_new_lines().await {
+                        state.push(Pane::HostLog, &line);
+                        dirty = true;
+                    }
+                }
+                let (rows, cols) = term.size();
+                if dirty || size != (rows, cols) {
+                    size = (rows, cols);
+                    dirty = false;
+                    let mut frame = String::new();
+                    for (row, line) in state.render(rows.into(), cols.into()).iter().enumerate() {
+                        let _ = write!(frame, "\x1b[{};1H{line}", row + 1);
+                    }
+                    let _ = term.write_str(&frame);
+                }
+            }
+            _ = &mut close => break,
+        }
+    }
+    let _ = term.clear_screen();
+    let _ = term.show_cursor();
+}
+
+/// Returns the most recently written host log in the given install directory, if any
+pub(crate) fn latest_host_log(install_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(install_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("wasmcloud_") && name.ends_with(".log")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Follows the lines appended to a log file
+struct LogTail {
+    path: PathBuf,
+    position: Option<u64>,
+    partial: Vec<u8>,
+}
+
+impl LogTail {
+    fn new(path: PathBuf) -> Self {
+        LogTail {
+            path,
+            position: None,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Returns the complete lines written since the last call, starting with the end of the file
+    async fn read_new_lines(&mut self) -> Vec<String> {
+        let mut file = match File::open(&self.path).await {
+            Ok(file) => file,
+            Err(_) => return Vec::new(),
+        };
+        let len = match file.metadata().await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Vec::new(),
+        };
+        let position = match self.position {
+            // The log was truncated, e.g. by a new host
+            Some(position) if position > len => {
+                self.partial.clear();
+                0
+            }
+            Some(position) => position,
+            None => len.saturating_sub(HOST_LOG_TAIL_BYTES),
+        };
+        let mut buf = Vec::new();
+        if file.seek(SeekFrom::Start(position)).await.is_err()
+            || file.read_to_end(&mut buf).await.is_err()
+        {
+            return Vec::new();
+        }
+        // Skip the line the tail starts in the middle of
+        if self.position.is_none() && position > 0 {
+            let start = buf
+                .iter()
+                .position(|b| *b == b'\n')
+                .map_or(buf.len(), |i| i + 1);
+            buf.drain(..start);
+        }
+        self.position = Some(position + buf.len() as u64);
+
+        self.partial.extend(buf);
+        let end = match self.partial.iter().rposition(|b| *b == b'\n') {
+            Some(end) => end,
+            None => return Vec::new(),
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_filter_and_pause_view() {
+        let mut keys = KeyMap::default();
+        let mut state = ViewState::default();
+        state.push(Pane::Build, "building project");
+        state.push(Pane::Invocations, "HttpServer.HandleRequest");
+        state.push(Pane::Invocations, "KeyValue.Get");
+
+        // Filters are typed, then applied with enter
+        for key in [Key::Char('/'), Key::Char('k'), Key::Char('e'), Key::Enter] {
+            if let Some(command) = keys.command(key) {
+                state.apply(command);
+            }
+        }
+        assert_eq!(state.filter, "ke");
+        assert_eq!(state.visible_lines(Pane::Invocations, 10), ["KeyValue.Get"]);
+        assert!(state.visible_lines(Pane::Build, 10).is_empty());
+        // Keys typed after the filter is applied are commands again
+        assert_eq!(keys.command(Key::Char('q')), Some(Command::Quit));
+        state.apply(keys.command(Key::Escape).unwrap());
+        assert_eq!(state.visible_lines(Pane::Invocations, 1), ["KeyValue.Get"]);
+
+        // Lines are held while paused
+        state.apply(keys.command(Key::Char('p')).unwrap());
+        state.push(Pane::Build, "build done");
+        assert_eq!(state.visible_lines(Pane::Build, 10), ["building project"]);
+        state.apply(keys.command(Key::Char('p')).unwrap());
+        assert_eq!(
+            state.visible_lines(Pane::Build, 10),
+            ["building project", "build done"]
+        );
+
+        // Hidden panes leave their rows to the others
+        state.apply(keys.command(Key::Char('3')).unwrap());
+        let frame = state.render(10, 40);
+        assert_eq!(frame.len(), 10);
+        assert!(frame
+            .iter()
+            .all(|row| console::measure_text_width(row) == 40));
+        assert!(!frame.iter().any(|row| row.contains("Host log")));
+    }
+}
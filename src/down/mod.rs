@@ -16,8 +16,9 @@ use wash_lib::start::{nats_pid_path, NATS_SERVER_BINARY, WADM_PID};
 use crate::appearance::spinner::Spinner;
 use crate::cfg::cfg_dir;
 use crate::up::{
-    DEFAULT_LATTICE_PREFIX, DOWNLOADS_DIR, WASMCLOUD_CTL_CREDSFILE, WASMCLOUD_CTL_HOST,
-    WASMCLOUD_CTL_JWT, WASMCLOUD_CTL_PORT, WASMCLOUD_CTL_SEED, WASMCLOUD_LATTICE_PREFIX,
+    read_started_hosts, write_started_hosts, StartedHost, DEFAULT_LATTICE_PREFIX, DOWNLOADS_DIR,
+    WASMCLOUD_CTL_CREDSFILE, WASMCLOUD_CTL_HOST, WASMCLOUD_CTL_JWT, WASMCLOUD_CTL_PORT,
+    WASMCLOUD_CTL_SEED, WASMCLOUD_LATTICE_PREFIX,
 };
 use crate::util::nats_client_from_opts;
 
@@ -77,6 +78,16 @@ pub(crate) async fn handle_down(
     let mut out_json = HashMap::new();
     let mut out_text = String::from("");
 
+    // Hosts started by `wash up` are all stopped, even if other hosts are running in the lattice
+    let started_hosts = read_started_hosts(&install_dir).await.unwrap_or_else(|e| {
+        warn!("Couldn't read the hosts started by wash up: {e}");
+        Vec::new()
+    });
+    let started_ids = started_hosts
+        .iter()
+        .map(|host| host.host_id.clone())
+        .collect::<Vec<_>>();
+
     if let Ok(client) = nats_client_from_opts(
         &cmd.ctl_host
             .unwrap_or_else(|| DEFAULT_NATS_HOST.to_string()),
@@ -89,8 +100,23 @@ pub(crate) async fn handle_down(
     )
     .await
     {
-        let (hosts, hosts_remain) =
-            stop_hosts(client, &cmd.lattice_prefix, &cmd.host_id, cmd.all).await?;
+        let (hosts, hosts_remain) = stop_hosts(
+            client,
+            &cmd.lattice_prefix,
+            &cmd.host_id,
+            cmd.all,
+            &started_ids,
+        )
+        .await?;
+        let still_started = if hosts_remain {
+            started_hosts
+                .into_iter()
+                .filter(|host| !hosts.contains(&host.host_id))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        write_started_hosts(&install_dir, &still_started).await?;
         out_json.insert("hosts_stopped".to_string(), json!(hosts));
         out_text.push_str("✅ wasmCloud hosts stopped successfully\n");
         if hosts_remain {
@@ -101,8 +127,18 @@ pub(crate) async fn handle_down(
             );
             return Ok(CommandOutput::new(out_text, out_json));
         }
-    } else {
+    } else if started_hosts.is_empty() {
         warn!("Couldn't connect to NATS, unable to stop running hosts")
+    } else {
+        warn!("Couldn't connect to NATS, killing the hosts started by wash up");
+        let hosts = kill_hosts(&started_hosts).await;
+        let still_started = started_hosts
+            .into_iter()
+            .filter(|host| !hosts.contains(&host.host_id))
+            .collect::<Vec<_>>();
+        write_started_hosts(&install_dir, &still_started).await?;
+        out_json.insert("hosts_stopped".to_string(), json!(hosts));
+        out_text.push_str("✅ wasmCloud hosts stopped successfully\n");
     }
 
     match stop_wadm(&install_dir).await {
@@ -139,12 +175,14 @@ pub(crate) async fn handle_down(
 }
 
 /// Stop running wasmCloud hosts, returns a vector of host IDs that were stopped and
-/// a boolean indicating whether any hosts remain running
+/// a boolean indicating whether any hosts remain running. When several hosts are running, the ones in `started`
+/// are stopped unless a host ID is supplied or `all` is set
 async fn stop_hosts(
     nats_client: Client,
     lattice_prefix: &str,
     host_id: &Option<ServerId>,
     all: bool,
+    started: &[String],
 ) -> Result<(Vec<String>, bool)> {
     let client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
         .lattice_prefix(lattice_prefix)
@@ -176,26 +214,21 @@ async fn stop_hosts(
             .map_err(|e| anyhow!(e))?;
         Ok((vec![host_id.to_string()], false))
     } else if all {
-        let host_stops = hosts
-            .iter()
-            .map(|host| async {
-                let host_id = &host.id;
-                match client.stop_host(host_id, None).await {
-                    Ok(_) => Some(host_id.to_owned()),
-                    Err(e) => {
-                        error!("Could not stop host {}: {:?}", host_id, e);
-                        None
-                    }
-                }
-            })
-            .collect::<Vec<_>>();
-        let all_stops = futures::future::join_all(host_stops).await;
-        let host_ids = all_stops
-            .iter()
-            // Remove any host IDs that ran into errors
-            .filter_map(|host_id| host_id.to_owned())
-            .collect::<Vec<_>>();
-        let hosts_remaining = all_stops.len() > host_ids.len();
+        let host_ids = stop_host_ids(&client, hosts.iter().map(|host| &host.id)).await;
+        let hosts_remaining = hosts.len() > host_ids.len();
+
+        Ok((host_ids, hosts_remaining))
+    } else if hosts.iter().any(|host| started.contains(&host.id)) {
+        // Stop the hosts started by `wash up`, leaving the others running
+        let host_ids = stop_host_ids(
+            &client,
+            hosts
+                .iter()
+                .map(|host| &host.id)
+                .filter(|host_id| started.contains(host_id)),
+        )
+        .await;
+        let hosts_remaining = hosts.len() > host_ids.len();
 
         Ok((host_ids, hosts_remaining))
     } else {
@@ -205,6 +238,62 @@ async fn stop_hosts(
     }
 }
 
+/// Stop the hosts with the given IDs, returns the IDs of the hosts that were stopped
+async fn stop_host_ids<'a>(
+    client: &wasmcloud_control_interface::Client,
+    host_ids: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    let host_stops = host_ids
+        .map(|host_id| async move {
+            match client.stop_host(host_id, None).await {
+                Ok(_) => Some(host_id.to_owned()),
+                Err(e) => {
+                    error!("Could not stop host {}: {:?}", host_id, e);
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    futures::future::join_all(host_stops)
+        .await
+        .into_iter()
+        // Remove any host IDs that ran into errors
+        .flatten()
+        .collect()
+}
+
+/// Kill the processes of hosts started by `wash up`, returns the IDs of the hosts that were killed
+async fn kill_hosts(hosts: &[StartedHost]) -> Vec<String> {
+    let mut killed = Vec::new();
+    for (host, pid) in hosts
+        .iter()
+        .filter_map(|host| host.pid.map(|pid| (host, pid)))
+    {
+        // Hosts are started in their own process group on unix, which is killed with them
+        #[cfg(target_family = "unix")]
+        let output = Command::new("kill")
+            .arg("--")
+            .arg(format!("-{pid}"))
+            .output()
+            .await;
+        #[cfg(target_family = "windows")]
+        let output = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output()
+            .await;
+        match output {
+            Ok(output) if output.status.success() => killed.push(host.host_id.clone()),
+            Ok(output) => error!(
+                "Could not kill host {}: {}",
+                host.host_id,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => error!("Could not kill host {}: {:?}", host.host_id, e),
+        }
+    }
+    killed
+}
+
 /// Helper function to send the nats-server the stop command
 pub(crate) async fn stop_nats<P>(install_dir: P) -> Result<Output>
 where
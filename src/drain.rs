@@ -50,5 +50,10 @@ mod test {
             Drain::Smithy => {}
             _ => panic!("drain constructed incorrect command"),
         }
+        let build: Cmd = Parser::try_parse_from(["drain", "build"]).unwrap();
+        match build.drain {
+            Drain::Build => {}
+            _ => panic!("drain constructed incorrect command"),
+        }
     }
 }
@@ -0,0 +1,111 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde_json::json;
+use wash_lib::cli::CommandOutput;
+use wash_lib::parser::{infer_config, InitOptions, TypeConfig};
+
+/// Generate a wasmcloud.toml file for an existing Cargo or TinyGo project
+#[derive(Debug, Clone, Parser)]
+pub(crate) struct InitCommand {
+    /// Path to the project directory
+    #[clap(name = "path", default_value = ".")]
+    path: PathBuf,
+
+    /// Type of the project. Inferred from the project when not set
+    #[clap(long = "type", value_parser = ["actor", "provider", "interface"])]
+    project_type: Option<String>,
+
+    /// Capability contract ID of a provider project, e.g. "wasmcloud:keyvalue". Inferred from the interface the
+    /// provider depends on when not set
+    #[clap(long = "capability-id")]
+    capability_id: Option<String>,
+
+    /// Overwrite an existing wasmcloud.toml file
+    #[clap(long = "force")]
+    force: bool,
+
+    /// Print the generated file instead of writing it
+    #[clap(long = "dry-run")]
+    dry_run: bool,
+}
+
+pub(crate) fn handle_command(command: InitCommand) -> Result<CommandOutput> {
+    let wasmcloud_path = command.path.join("wasmcloud.toml");
+    if wasmcloud_path.exists() && !command.force && !command.dry_run {
+        bail!(
+            "{} already exists, use --force to overwrite it",
+            wasmcloud_path.display()
+        )
+    }
+
+    let config = infer_config(
+        &command.path,
+        &InitOptions {
+            project_type: command.project_type,
+            capability_id: command.capability_id,
+        },
+    )?;
+    let value = config.to_minimal_file_value()?;
+    let contents = toml::to_string_pretty(&value)?;
+
+    let (project_type, claims) = match &config.project_type {
+        TypeConfig::Actor(actor) => ("actor", actor.claims.clone()),
+        TypeConfig::Provider(provider) => ("provider", vec![provider.capability_id.clone()]),
+        TypeConfig::Interface(_) => ("interface", Vec::new()),
+    };
+    let mut map = HashMap::from([
+        ("config".to_string(), value),
+        ("type".to_string(), json!(project_type)),
+        ("claims".to_string(), json!(claims)),
+    ]);
+
+    if command.dry_run {
+        return Ok(CommandOutput::new(contents, map));
+    }
+    fs::write(&wasmcloud_path, &contents)?;
+    map.insert("path".to_string(), json!(wasmcloud_path));
+
+    let mut text = format!(
+        "Created {} for {project_type} {} v{}",
+        wasmcloud_path.display(),
+        config.common.name,
+        config.common.version
+    );
+    if !claims.is_empty() {
+        text.push_str(&format!(" with claims {}", claims.join(", ")));
+    }
+    Ok(CommandOutput::new(text, map))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_init_comprehensive() {
+        let cmd: InitCommand = Parser::try_parse_from([
+            "init",
+            "./provider",
+            "--type",
+            "provider",
+            "--capability-id",
+            "wasmcloud:keyvalue",
+            "--force",
+        ])
+        .unwrap();
+        assert_eq!(cmd.path, PathBuf::from("./provider"));
+        assert_eq!(cmd.project_type, Some("provider".to_string()));
+        assert_eq!(cmd.capability_id, Some("wasmcloud:keyvalue".to_string()));
+        assert!(cmd.force);
+        assert!(!cmd.dry_run);
+
+        let cmd: InitCommand = Parser::try_parse_from(["init", "--dry-run"]).unwrap();
+        assert_eq!(cmd.path, PathBuf::from("."));
+        assert!(cmd.project_type.is_none());
+        assert!(cmd.dry_run);
+
+        assert!(InitCommand::try_parse_from(["init", "--type", "library"]).is_err());
+    }
+}
@@ -25,10 +25,12 @@ use build::BuildCommand;
 use call::CallCli;
 use clap::{Parser, Subcommand};
 use completions::CompletionOpts;
+use config::ConfigCliCommand;
 use ctl::CtlCliCommand;
 use ctx::CtxCommand;
 use down::DownCommand;
 use generate::NewCliCommand;
+use init::InitCommand;
 use keys::KeysCliCommand;
 use par::ParCliCommand;
 use up::UpCommand;
@@ -40,12 +42,14 @@ mod call;
 mod cfg;
 mod common;
 mod completions;
+mod config;
 mod ctl;
 mod ctx;
 mod dev;
 mod down;
 mod drain;
 mod generate;
+mod init;
 mod keys;
 mod par;
 mod smithy;
@@ -68,6 +72,7 @@ Usage: wash [OPTIONS] <COMMAND>
 
 Build:
   new          Create a new project from template
+  init         Generate a wasmcloud.toml file for an existing Cargo or TinyGo project
   build        Build (and sign) a wasmCloud actor, capability provider, or interface
   dev          Run a actor development loop (experimental)
   inspect      Inspect capability provider or actor module
@@ -94,6 +99,7 @@ Publish:
 
 Configure:
   completions  Generate shell completions for wash
+  config       Validate and show wasmcloud.toml files, and print their JSON Schema
   ctx          Manage wasmCloud host configuration contexts
   drain        Manage contents of local wasmCloud caches
   keys         Utilities for generating and managing keys
@@ -159,6 +165,9 @@ enum CliCommand {
     /// Generate and manage JWTs for wasmCloud actors
     #[clap(name = "claims", subcommand)]
     Claims(ClaimsCliCommand),
+    /// Validate and show wasmcloud.toml files, and print their JSON Schema
+    #[clap(name = "config", subcommand)]
+    Config(ConfigCliCommand),
     /// Interact with a wasmCloud control interface
     #[clap(name = "ctl", subcommand)]
     Ctl(CtlCliCommand),
@@ -180,6 +189,9 @@ enum CliCommand {
     /// Get information about different resources
     #[clap(name = "get", subcommand)]
     Get(GetCommand),
+    /// Generate a wasmcloud.toml file for an existing Cargo or TinyGo project
+    #[clap(name = "init")]
+    Init(InitCommand),
     /// Inspect capability provider or actor module
     #[clap(name = "inspect")]
     Inspect(InspectCliCommand),
@@ -226,15 +238,17 @@ enum CliCommand {
 
 #[tokio::main]
 async fn main() {
-    use clap::CommandFactory;
+    use clap::{CommandFactory, FromArgMatches};
     if env_logger::try_init().is_err() {}
-    let cli: Cli = Parser::parse();
+    // The matches are kept to tell the flags given on the command line apart from default values
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     let output_kind = cli.output;
 
     let res: Result<CommandOutput> = match cli.command {
         CliCommand::App(app_cli) => app::handle_command(app_cli, output_kind).await,
-        CliCommand::Build(build_cli) => build::handle_command(build_cli),
+        CliCommand::Build(build_cli) => build::handle_command(build_cli).await,
         CliCommand::Call(call_cli) => call::handle_command(call_cli.command()).await,
         CliCommand::Capture(capture_cli) => {
             if !cli.experimental {
@@ -251,6 +265,7 @@ async fn main() {
         CliCommand::Completions(completions_cli) => {
             completions::handle_command(completions_cli, Cli::command())
         }
+        CliCommand::Config(config_cli) => config::handle_command(config_cli),
         CliCommand::Ctl(ctl_cli) => ctl::handle_command(ctl_cli, output_kind).await,
         CliCommand::Ctx(ctx_cli) => ctx::handle_command(ctx_cli).await,
         CliCommand::Dev(dev_cli) => {
@@ -264,6 +279,7 @@ async fn main() {
         CliCommand::Drain(drain_cli) => drain::handle_command(drain_cli),
         CliCommand::Get(get_cli) => common::get_cmd::handle_command(get_cli, output_kind).await,
         CliCommand::Gen(generate_cli) => smithy::handle_gen_command(generate_cli),
+        CliCommand::Init(init_cli) => init::handle_command(init_cli),
         CliCommand::Inspect(inspect_cli) => {
             wash_lib::cli::inspect::handle_command(inspect_cli, output_kind).await
         }
@@ -292,18 +308,33 @@ async fn main() {
             common::start_cmd::handle_command(start_cli, output_kind).await
         }
         CliCommand::Stop(stop_cli) => common::stop_cmd::handle_command(stop_cli, output_kind).await,
-        CliCommand::Up(up_cli) => up::handle_command(up_cli, output_kind).await,
+        CliCommand::Up(up_cli) => {
+            let up_matches = matches
+                .subcommand_matches("up")
+                .expect("up command should be parsed from the up subcommand");
+            up::handle_command(up_cli, up_matches, output_kind).await
+        }
         CliCommand::Validate(validate_cli) => smithy::handle_validate_command(validate_cli).await,
     };
 
     std::process::exit(match res {
         Ok(out) => {
+            // Commands that complete with partial failures (e.g. a workspace build) report them in their output
+            let success = out
+                .map
+                .get("success")
+                .and_then(|success| success.as_bool())
+                .unwrap_or(true);
             match output_kind {
                 OutputKind::Json => {
                     let mut map = out.map;
-                    map.insert("success".to_string(), json!(true));
+                    map.insert("success".to_string(), json!(success));
                     println!("\n{}", serde_json::to_string_pretty(&map).unwrap());
-                    0
+                    i32::from(!success)
+                }
+                OutputKind::Text if !success => {
+                    println!("\n{}", out.text);
+                    1
                 }
                 OutputKind::Text => {
                     println!("\n{}", out.text);
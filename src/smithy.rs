@@ -7,10 +7,7 @@ use atelier_core::model::Model;
 use clap::Parser;
 use console::style;
 use wash_lib::cli::CommandOutput;
-use weld_codegen::{
-    config::{CodegenConfig, ModelSource, OutputLanguage},
-    sources_to_model,
-};
+use weld_codegen::config::{CodegenConfig, ModelSource, OutputLanguage};
 
 use wash_lib::generate::emoji;
 
@@ -172,7 +169,7 @@ pub(crate) async fn handle_validate_command(command: ValidateCli) -> Result<Comm
     Ok(CommandOutput::default())
 }
 
-/// build model from input files and/or files listed in codegen.toml.
+/// build model from input files provided on the command line, or else the files listed in codegen.toml.
 /// Dependent models may be downloaded by a background thread.
 fn build_model(
     input: Vec<String>,
@@ -180,31 +177,17 @@ fn build_model(
     base_dir: PathBuf,
     verbose: u8,
 ) -> Result<Model, anyhow::Error> {
-    // The downloader crate (used by sources_to_model) creates a tokio Runtime
-    // and calls block_on(), but since we're already in a Runtime created by main().
-    // that panics. Using thread::spawn here allows the second Runtime.
-    std::thread::spawn(move || {
-        if input.is_empty() {
-            sources_to_model(&models, &base_dir, verbose).map_err(|e| e.to_string())
-        } else {
-            inputs_to_model(&input, verbose).map_err(|e| e.to_string())
-        }
-    })
-    .join()
-    .map_err(|_| anyhow!("downloader thread paniced"))?
-    .map_err(|e| anyhow!("{}", e))
-}
-
-/// build model from inputs files provided on the command line
-fn inputs_to_model(inputs: &[String], verbose: u8) -> Result<Model, anyhow::Error> {
-    use std::str::FromStr;
-    let inputs = inputs
-        .iter()
-        // unwrap below ok because this from_str is Infallible
-        .map(|s| ModelSource::from_str(s).unwrap())
-        .collect::<Vec<ModelSource>>();
-    let current_dir = PathBuf::from(".");
-    Ok(sources_to_model(&inputs, &current_dir, verbose)?)
+    if input.is_empty() {
+        wash_lib::build::build_model(models, base_dir, verbose)
+    } else {
+        use std::str::FromStr;
+        let inputs = input
+            .iter()
+            // unwrap below ok because this from_str is Infallible
+            .map(|s| ModelSource::from_str(s).unwrap())
+            .collect::<Vec<ModelSource>>();
+        wash_lib::build::build_model(inputs, PathBuf::from("."), verbose)
+    }
 }
 
 /// identify config file from command-line, current-directory, or built-in default
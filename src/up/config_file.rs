@@ -0,0 +1,250 @@
+//! Options of `wash up` loaded from a configuration file, so a local environment can be shared instead of long
+//! lists of flags. Options are named after the arguments of `wash up`, grouped in the sections below, and named
+//! profiles apply their own sections over the rest of the file:
+//!
+//! ```toml
+//! detached = true
+//!
+//! [nats]
+//! nats_port = 4222
+//!
+//! [wasmcloud]
+//! lattice_prefix = "default"
+//! allow_file_load = true
+//!
+//! [profiles.ci.wasmcloud]
+//! lattice_prefix = "ci"
+//! enable_structured_logging = true
+//! ```
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{parser::ValueSource, ArgMatches};
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use super::{NatsOpts, UpCommand, WadmOpts, WasmcloudOpts};
+
+/// Table of the profiles in a configuration file
+const PROFILES_KEY: &str = "profiles";
+/// Options of the `wasmcloud` section holding seeds and credentials, which are never printed
+const SECRET_OPTIONS: [&str; 8] = [
+    "host_seed",
+    "cluster_seed",
+    "rpc_seed",
+    "rpc_jwt",
+    "prov_rpc_seed",
+    "prov_rpc_jwt",
+    "ctl_seed",
+    "ctl_jwt",
+];
+/// Value printed in place of a secret option
+const REDACTED: &str = "<redacted>";
+
+/// The options of `wash up` that can be set in a configuration file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct UpConfig {
+    pub(crate) detached: bool,
+    pub(crate) hosts: u16,
+    pub(crate) nats: NatsOpts,
+    pub(crate) wasmcloud: WasmcloudOpts,
+    pub(crate) wadm: WadmOpts,
+}
+
+impl From<&UpCommand> for UpConfig {
+    fn from(cmd: &UpCommand) -> UpConfig {
+        UpConfig {
+            detached: cmd.detached,
+            hosts: cmd.hosts,
+            nats: cmd.nats_opts.clone(),
+            wasmcloud: cmd.wasmcloud_opts.clone(),
+            wadm: cmd.wadm_opts.clone(),
+        }
+    }
+}
+
+impl UpConfig {
+    /// Replaces the options of `cmd` with these ones
+    pub(crate) fn apply_to(self, cmd: &mut UpCommand) {
+        cmd.detached = self.detached;
+        cmd.hosts = self.hosts;
+        cmd.nats_opts = self.nats;
+        cmd.wasmcloud_opts = self.wasmcloud;
+        cmd.wadm_opts = self.wadm;
+    }
+
+    /// Returns these options as a table, with the seeds and credentials that are set replaced so they can be printed
+    pub(crate) fn redacted(&self) -> Result<Table> {
+        let mut config = Table::try_from(self)?;
+        if let Some(Value::Table(wasmcloud)) = config.get_mut("wasmcloud") {
+            for option in SECRET_OPTIONS {
+                if let Some(value) = wasmcloud.get_mut(option) {
+                    *value = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Loads the configuration file at `path`, with the given profile applied, and fills in the options of `cmd` that
+/// were not given on the command line or through an environment variable. `matches` are the arguments `cmd` was
+/// parsed from.
+pub(crate) fn load_config_file(
+    cmd: &UpCommand,
+    matches: &ArgMatches,
+    path: &Path,
+    profile: Option<&str>,
+) -> Result<UpConfig> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read wash up configuration [{}]", path.display()))?;
+    let mut file: Table = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse wash up configuration [{}]", path.display()))?;
+
+    let mut profiles = match file.remove(PROFILES_KEY) {
+        Some(Value::Table(profiles)) => profiles,
+        Some(_) => bail!("[{PROFILES_KEY}] must be a table of profiles"),
+        None => Table::new(),
+    };
+    if let Some(name) = profile {
+        match profiles.remove(name) {
+            Some(Value::Table(overlay)) => merge_tables(&mut file, overlay),
+            Some(_) => bail!("profile [{name}] must be a table"),
+            None => {
+                let available = profiles.keys().cloned().collect::<Vec<_>>();
+                bail!(
+                    "profile [{name}] not found in [{}], expected one of: {}",
+                    path.display(),
+                    available.join(", ")
+                )
+            }
+        }
+    }
+
+    let mut config = Table::try_from(UpConfig::from(cmd))?;
+    for (key, value) in file {
+        match (config.get_mut(&key), value) {
+            (Some(Value::Table(section)), Value::Table(options)) => {
+                for (id, value) in options {
+                    set_option(section, matches, id, value)?;
+                }
+            }
+            (_, value) => set_option(&mut config, matches, key, value)?,
+        }
+    }
+    Value::Table(config).try_into().with_context(|| {
+        format!(
+            "invalid option in wash up configuration [{}]",
+            path.display()
+        )
+    })
+}
+
+/// Sets an option from the configuration file, unless it was given on the command line or through an environment
+/// variable
+fn set_option(options: &mut Table, matches: &ArgMatches, id: String, value: Value) -> Result<()> {
+    matches
+        .try_contains_id(&id)
+        .map_err(|_| anyhow!("unknown option [{id}] in wash up configuration"))?;
+    if !matches!(
+        matches.value_source(&id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    ) {
+        options.insert(id, value);
+    }
+    Ok(())
+}
+
+/// Deep-merges `overlay` into `base`. Tables are merged key by key, any other value replaces the one in `base`.
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(value)) => merge_tables(existing, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+detached = true
+
+[nats]
+nats_port = 4333
+
+[wasmcloud]
+lattice_prefix = "team"
+allow_latest = true
+
+[profiles.ci.wasmcloud]
+lattice_prefix = "ci"
+enable_structured_logging = true
+"#;
+
+    fn load(args: &[&str], profile: Option<&str>) -> Result<UpConfig> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(CONFIG.as_bytes())?;
+        let matches = UpCommand::command().try_get_matches_from(args)?;
+        let cmd = UpCommand::from_arg_matches(&matches)?;
+        load_config_file(&cmd, &matches, file.path(), profile)
+    }
+
+    #[test]
+    fn can_load_config_file_with_profile() -> Result<()> {
+        let config = load(&["up"], None)?;
+        assert!(config.detached);
+        assert_eq!(config.nats.nats_port, 4333);
+        assert_eq!(config.wasmcloud.lattice_prefix, "team");
+        assert!(config.wasmcloud.allow_latest);
+        assert!(!config.wasmcloud.enable_structured_logging);
+
+        let config = load(&["up"], Some("ci"))?;
+        assert_eq!(config.nats.nats_port, 4333);
+        assert_eq!(config.wasmcloud.lattice_prefix, "ci");
+        assert!(config.wasmcloud.enable_structured_logging);
+
+        assert!(load(&["up"], Some("staging")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn redacts_secrets() -> Result<()> {
+        let seed = "SCAOGJWX53TGI4233T6GAO3RKQUS4IQSFQOZ3XNSAJSAHVLCPJ6MAB3X3A";
+        let mut config = load(&["up", "--host-seed", seed, "--cluster-seed", seed], None)?;
+        config.wasmcloud.ctl_jwt = Some("eyJ0eXAiOiJKV1QiLCJhbGciOiJlZDI1NTE5In0".to_string());
+
+        let redacted = config.redacted()?;
+        let printed = toml::to_string_pretty(&redacted)?;
+        assert!(!printed.contains(seed));
+        assert!(!printed.contains("eyJ0eXAi"));
+        assert_eq!(redacted["wasmcloud"]["host_seed"].as_str(), Some(REDACTED));
+        assert_eq!(redacted["wasmcloud"]["ctl_jwt"].as_str(), Some(REDACTED));
+        // Options that aren't set stay unset, and other options are printed as they are
+        assert!(redacted["wasmcloud"].get("rpc_seed").is_none());
+        assert_eq!(
+            redacted["wasmcloud"]["lattice_prefix"].as_str(),
+            Some("team")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn flags_override_config_file() -> Result<()> {
+        let config = load(&["up", "--nats-port", "5222", "-x", "mine"], Some("ci"))?;
+        assert_eq!(config.nats.nats_port, 5222);
+        assert_eq!(config.wasmcloud.lattice_prefix, "mine");
+        assert!(config.wasmcloud.allow_latest);
+        Ok(())
+    }
+}
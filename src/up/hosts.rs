@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// File in the install directory listing the hosts started by `wash up --detached`
+pub const WASMCLOUD_HOSTS_FILE: &str = "wasmcloud_hosts.json";
+/// Prefix of the environment variables that set the labels of a wasmCloud host
+const HOST_LABEL_ENV_PREFIX: &str = "HOST_";
+
+/// A wasmCloud host started in the background by `wash up`, tracked so `wash down` can stop it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StartedHost {
+    pub(crate) host_id: String,
+    /// Process ID of the host, used to kill it when it can't be stopped through the lattice
+    pub(crate) pid: Option<u32>,
+    pub(crate) dashboard_port: u16,
+    pub(crate) log_file: PathBuf,
+}
+
+/// Returns the hosts started by `wash up` that haven't been stopped by `wash down`
+pub(crate) async fn read_started_hosts(install_dir: &Path) -> Result<Vec<StartedHost>> {
+    let path = install_dir.join(WASMCLOUD_HOSTS_FILE);
+    match tokio::fs::read(&path).await {
+        Ok(contents) => serde_json::from_slice(&contents)
+            .with_context(|| format!("failed to parse started hosts from [{}]", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Records the hosts started by `wash up` that are still running, removing the file when there are none
+pub(crate) async fn write_started_hosts(install_dir: &Path, hosts: &[StartedHost]) -> Result<()> {
+    let path = install_dir.join(WASMCLOUD_HOSTS_FILE);
+    if hosts.is_empty() {
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    } else {
+        tokio::fs::write(&path, serde_json::to_vec_pretty(hosts)?)
+            .await
+            .with_context(|| format!("failed to record started hosts in [{}]", path.display()))
+    }
+}
+
+/// Resolves the labels given to `wash up` into the environment variables setting the labels of each of the `hosts`
+/// hosts it starts. Labels are formatted as `key=value`, or as `number:key=value` to only set them on the host
+/// with that number, starting from 1
+pub(crate) fn host_label_envs(
+    labels: &[String],
+    hosts: u16,
+) -> Result<Vec<HashMap<String, String>>> {
+    let mut envs = vec![HashMap::new(); hosts.into()];
+    for label in labels {
+        let (target, value) = label
+            .split_once('=')
+            .ok_or_else(|| anyhow!("label [{label}] must be formatted as key=value"))?;
+        let (number, key) = match target.split_once(':') {
+            Some((number, key)) => {
+                let number = number
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|number| (1..=hosts).contains(number))
+                    .ok_or_else(|| {
+                        anyhow!("label [{label}] must start with a host number from 1 to {hosts}")
+                    })?;
+                (Some(number), key)
+            }
+            None => (None, target),
+        };
+        if key.is_empty() {
+            bail!("label [{label}] must have a key");
+        }
+        for (index, env) in envs.iter_mut().enumerate() {
+            if number.map_or(true, |number| usize::from(number) == index + 1) {
+                env.insert(format!("{HOST_LABEL_ENV_PREFIX}{key}"), value.to_string());
+            }
+        }
+    }
+    Ok(envs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_resolve_host_labels() -> Result<()> {
+        let labels = vec![
+            "team=platform".to_string(),
+            "1:zone=east".to_string(),
+            "2:zone=west".to_string(),
+        ];
+        let envs = host_label_envs(&labels, 3)?;
+        assert_eq!(envs.len(), 3);
+        assert_eq!(envs[0]["HOST_team"], "platform");
+        assert_eq!(envs[0]["HOST_zone"], "east");
+        assert_eq!(envs[1]["HOST_zone"], "west");
+        assert_eq!(envs[2].len(), 1);
+        assert_eq!(envs[2]["HOST_team"], "platform");
+
+        assert!(host_label_envs(&["zone".to_string()], 1).is_err());
+        assert!(host_label_envs(&["4:zone=east".to_string()], 3).is_err());
+        assert!(host_label_envs(&["0:zone=east".to_string()], 3).is_err());
+        assert!(host_label_envs(&["=east".to_string()], 3).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn can_track_started_hosts() -> Result<()> {
+        let install_dir = tempfile::tempdir()?;
+        assert!(read_started_hosts(install_dir.path()).await?.is_empty());
+
+        let hosts = vec![StartedHost {
+            host_id: "NCXCPFJQGUAWNA2HMGDCDO4EYAZQCPPUPS7EIXR5TWTXDM2WVV3MLTWH".to_string(),
+            pid: Some(4242),
+            dashboard_port: 4000,
+            log_file: install_dir.path().join("wasmcloud_4000.log"),
+        }];
+        write_started_hosts(install_dir.path(), &hosts).await?;
+        assert_eq!(read_started_hosts(install_dir.path()).await?, hosts);
+
+        write_started_hosts(install_dir.path(), &[]).await?;
+        assert!(!install_dir.path().join(WASMCLOUD_HOSTS_FILE).exists());
+        Ok(())
+    }
+}
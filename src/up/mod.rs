@@ -11,7 +11,9 @@ use std::sync::{
 
 use anyhow::{anyhow, Context, Result};
 use async_nats::Client;
-use clap::Parser;
+use clap::{ArgMatches, Parser};
+use nkeys::KeyPair;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use tokio::fs::create_dir_all;
@@ -38,8 +40,12 @@ use crate::down::stop_nats;
 use crate::util::nats_client_from_opts;
 
 mod config;
+mod config_file;
 mod credsfile;
+mod hosts;
 pub use config::*;
+use config_file::{load_config_file, UpConfig};
+pub(crate) use hosts::*;
 
 const LOCALHOST: &str = "127.0.0.1";
 
@@ -49,6 +55,11 @@ pub(crate) struct UpCommand {
     #[clap(short = 'd', long = "detached", alias = "detach")]
     pub(crate) detached: bool,
 
+    /// Number of wasmCloud hosts to start against the same NATS server and lattice, e.g. to try out auctions, host
+    /// constraints and failover locally. Each host gets its own host seed, dashboard port and log file
+    #[clap(long = "hosts", default_value = "1", value_parser = clap::value_parser!(u16).range(1..))]
+    pub(crate) hosts: u16,
+
     #[clap(flatten)]
     pub(crate) nats_opts: NatsOpts,
 
@@ -57,9 +68,23 @@ pub(crate) struct UpCommand {
 
     #[clap(flatten)]
     pub(crate) wadm_opts: WadmOpts,
+
+    /// Path to a TOML file to load the options of `wash up` from. Options given as flags or environment variables
+    /// take precedence over the ones in the file
+    #[clap(long = "config", env = "WASH_UP_CONFIG")]
+    pub(crate) config: Option<PathBuf>,
+
+    /// Name of the profile from the configuration file to apply over the rest of the file, e.g. "ci"
+    #[clap(long = "profile", env = "WASH_UP_PROFILE", requires = "config")]
+    pub(crate) profile: Option<String>,
+
+    /// Print the effective configuration, in the format of a configuration file, instead of starting anything
+    #[clap(long = "print-config")]
+    pub(crate) print_config: bool,
 }
 
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct NatsOpts {
     /// Optional path to a NATS credentials file to authenticate and extend existing NATS infrastructure.
     #[clap(
@@ -115,7 +140,8 @@ impl From<NatsOpts> for NatsConfig {
     }
 }
 
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct WasmcloudOpts {
     /// wasmCloud host version to download, e.g. `v0.55.0`. See https://github.com/wasmCloud/wasmcloud-otp/releases for releases
     #[clap(long = "wasmcloud-version", default_value = WASMCLOUD_HOST_VERSION, env = "WASMCLOUD_VERSION")]
@@ -134,6 +160,11 @@ pub(crate) struct WasmcloudOpts {
     #[clap(long = "host-seed", env = WASMCLOUD_HOST_SEED)]
     pub(crate) host_seed: Option<String>,
 
+    /// A label to set on the host, formatted as `key=value`. When starting several hosts with `--hosts`, prefix it
+    /// with the number of a host to only set it on that one, e.g. `2:zone=west`. Can be given several times
+    #[clap(long = "label")]
+    pub(crate) labels: Option<Vec<String>>,
+
     /// An IP address or DNS name to use to connect to NATS for RPC messages, defaults to the value supplied to --nats-host if not supplied
     #[clap(long = "rpc-host", env = WASMCLOUD_RPC_HOST)]
     pub(crate) rpc_host: Option<String>,
@@ -267,23 +298,29 @@ pub(crate) struct WasmcloudOpts {
 }
 
 impl WasmcloudOpts {
-    pub async fn into_ctl_client(self, auction_timeout_ms: Option<u64>) -> Result<CtlClient> {
-        let lattice_prefix = self.lattice_prefix;
+    /// Connect to the NATS server of the control interface
+    pub async fn nats_client(&self) -> Result<async_nats::Client> {
         let ctl_host = self
             .ctl_host
+            .clone()
             .unwrap_or_else(|| DEFAULT_NATS_HOST.to_string());
         let ctl_port = self.ctl_port.unwrap_or(4222).to_string();
-        let auction_timeout_ms = auction_timeout_ms.unwrap_or(DEFAULT_NATS_TIMEOUT_MS);
 
-        let nc = nats_client_from_opts(
+        nats_client_from_opts(
             &ctl_host,
             &ctl_port,
-            self.ctl_jwt,
-            self.ctl_seed,
-            self.ctl_credsfile,
+            self.ctl_jwt.clone(),
+            self.ctl_seed.clone(),
+            self.ctl_credsfile.clone(),
         )
         .await
-        .context("Failed to create NATS client")?;
+        .context("Failed to create NATS client")
+    }
+
+    pub async fn into_ctl_client(self, auction_timeout_ms: Option<u64>) -> Result<CtlClient> {
+        let auction_timeout_ms = auction_timeout_ms.unwrap_or(DEFAULT_NATS_TIMEOUT_MS);
+        let nc = self.nats_client().await?;
+        let lattice_prefix = self.lattice_prefix;
 
         let mut builder = CtlClientBuilder::new(nc)
             .lattice_prefix(lattice_prefix)
@@ -309,7 +346,8 @@ impl WasmcloudOpts {
     }
 }
 
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct WadmOpts {
     /// wadm version to download, e.g. `v0.4.0`. See https://github.com/wasmCloud/wadm/releases for releases
     #[clap(long = "wadm-version", default_value = WADM_VERSION, env = "WADM_VERSION")]
@@ -319,10 +357,28 @@ pub(crate) struct WadmOpts {
     pub(crate) disable_wadm: bool,
 }
 
+/// Runs `wash up`, with the options from its configuration file when one is given. `matches` are the arguments
+/// the command was parsed from, used to tell the options given on the command line apart from defaults
 pub(crate) async fn handle_command(
-    command: UpCommand,
+    mut command: UpCommand,
+    matches: &ArgMatches,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
+    if let Some(path) = &command.config {
+        load_config_file(&command, matches, path, command.profile.as_deref())?
+            .apply_to(&mut command);
+    }
+
+    if command.print_config {
+        let config = UpConfig::from(&command).redacted()?;
+        let mut map = HashMap::new();
+        map.insert("config".to_string(), serde_json::to_value(&config)?);
+        return Ok(CommandOutput::new(
+            toml::to_string_pretty(&config).context("failed to serialize configuration")?,
+            map,
+        ));
+    }
+
     handle_up(command, output_kind).await
 }
 
@@ -331,11 +387,26 @@ pub(crate) async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result
     create_dir_all(&install_dir).await?;
     let spinner = Spinner::new(&output_kind)?;
 
-    // Find an open port for the host, and if the user specified a port, ensure it's open
-    let host_port = ensure_open_port(cmd.wasmcloud_opts.dashboard_port).await?;
+    // Resolve the seed and labels of every host before starting anything. The first host uses the supplied seed
+    let host_labels = host_label_envs(
+        cmd.wasmcloud_opts.labels.as_deref().unwrap_or_default(),
+        cmd.hosts,
+    )?;
+    let mut host_seeds = Vec::with_capacity(cmd.hosts.into());
+    for index in 0..cmd.hosts {
+        let host_key = match (index, &cmd.wasmcloud_opts.host_seed) {
+            (0, Some(seed)) => KeyPair::from_seed(seed).context("Invalid host seed")?,
+            _ => KeyPair::new_server(),
+        };
+        host_seeds.push((host_key.seed()?, host_key.public_key()));
+    }
+
+    // Find an open port for each host, and if the user specified a port, ensure it's open
+    let host_ports = ensure_open_ports(cmd.wasmcloud_opts.dashboard_port, cmd.hosts).await?;
+    let host_port = host_ports[0];
 
     // Ensure we use the open dashboard port and the supplied NATS host/port if no overrides were supplied
-    let wasmcloud_opts = WasmcloudOpts {
+    let mut wasmcloud_opts = WasmcloudOpts {
         dashboard_port: Some(host_port),
         ctl_host: Some(
             cmd.wasmcloud_opts
@@ -456,57 +527,86 @@ pub(crate) async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result
         return Err(anyhow!("wasmCloud was not installed, exiting without downloading as --wasmcloud-start-only was set"));
     };
 
-    // Redirect output (which is on stderr) to a log file in detached mode, or use the terminal
-    spinner.update_spinner_message(" Starting wasmCloud ...".to_string());
-    let wasmcloud_log_path = install_dir.join(format!("wasmcloud_{host_port}.log"));
-    let stderr: Stdio = if cmd.detached {
-        tokio::fs::File::create(&wasmcloud_log_path)
-            .await?
-            .into_std()
-            .await
-            .into()
-    } else {
-        Stdio::piped()
-    };
+    // Hosts only accept invocations signed by a cluster key they know, so the hosts started together share one
+    if cmd.hosts > 1 && wasmcloud_opts.cluster_seed.is_none() {
+        wasmcloud_opts.cluster_seed = Some(KeyPair::new_cluster().seed()?);
+    }
     let version = wasmcloud_opts.wasmcloud_version.clone();
-
     let host_env = configure_host_env(nats_opts, wasmcloud_opts).await;
-    let wasmcloud_child = match start_wasmcloud_host(
-        &wasmcloud_executable,
-        std::process::Stdio::null(),
-        stderr,
-        host_env,
-    )
-    .await
+
+    // Start every host with its own seed, dashboard port, labels and log file
+    spinner.update_spinner_message(" Starting wasmCloud ...".to_string());
+    let mut started_hosts = Vec::with_capacity(host_ports.len());
+    let mut host_children = Vec::with_capacity(host_ports.len());
+    for ((port, (host_seed, host_id)), labels) in host_ports.iter().zip(host_seeds).zip(host_labels)
     {
-        Ok(child) => child,
-        Err(e) => {
-            // Ensure we clean up the NATS server and wadm if we can't start wasmCloud
-            if let Some(child) = wadm_process {
-                stop_wadm(child, &install_dir).await?;
+        let mut env = host_env.clone();
+        env.insert(WASMCLOUD_HOST_SEED.to_string(), host_seed);
+        env.insert(WASMCLOUD_DASHBOARD_PORT.to_string(), port.to_string());
+        env.extend(labels);
+
+        // Redirect output (which is on stderr) to a log file in detached mode or when starting several hosts, or
+        // use the terminal
+        let log_file = install_dir.join(format!("wasmcloud_{port}.log"));
+        let stderr: Stdio = if cmd.detached || cmd.hosts > 1 {
+            tokio::fs::File::create(&log_file)
+                .await?
+                .into_std()
+                .await
+                .into()
+        } else {
+            Stdio::piped()
+        };
+
+        match start_wasmcloud_host(
+            &wasmcloud_executable,
+            std::process::Stdio::null(),
+            stderr,
+            env,
+        )
+        .await
+        {
+            Ok(child) => {
+                started_hosts.push(StartedHost {
+                    host_id,
+                    pid: child.id(),
+                    dashboard_port: *port,
+                    log_file,
+                });
+                host_children.push(child);
             }
-            if !cmd.nats_opts.connect_only {
-                stop_nats(install_dir).await?;
+            Err(e) => {
+                // Ensure we clean up the other hosts, NATS server and wadm if we can't start wasmCloud
+                clean_up_failed_start(
+                    host_children,
+                    wadm_process,
+                    nats_bin.is_some(),
+                    &install_dir,
+                )
+                .await?;
+                return Err(e);
             }
-            return Err(e);
         }
-    };
+    }
 
-    let url = format!("{LOCALHOST}:{}", host_port);
-    if wait_for_server(&url, "Washboard").await.is_err() {
-        // Ensure we clean up the NATS server and wadm if we can't start wasmCloud
-        if let Some(child) = wadm_process {
-            stop_wadm(child, &install_dir).await?;
-        }
-        if nats_bin.is_some() {
-            stop_nats(install_dir).await?;
+    for host in &started_hosts {
+        let url = format!("{LOCALHOST}:{}", host.dashboard_port);
+        if wait_for_server(&url, "Washboard").await.is_err() {
+            // Ensure we clean up the hosts, NATS server and wadm if we can't start wasmCloud
+            clean_up_failed_start(
+                host_children,
+                wadm_process,
+                nats_bin.is_some(),
+                &install_dir,
+            )
+            .await?;
+            return Err(anyhow!("wasmCloud host did not start. Failed to connect to washboard. Check host-logs at {:?}.", host.log_file));
         }
-        return Err(anyhow!("wasmCloud host did not start. Failed to connect to washboard. Check host-logs at {:?}.", wasmcloud_log_path));
     }
 
     spinner.finish_and_clear();
     if !cmd.detached {
-        run_wasmcloud_interactive(wasmcloud_child, host_port, output_kind).await?;
+        run_wasmcloud_interactive(host_children, &started_hosts, output_kind).await?;
 
         let spinner = Spinner::new(&output_kind)?;
         spinner.update_spinner_message(
@@ -529,11 +629,19 @@ pub(crate) async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result
     out_text.push_str("🛁 wash up completed successfully");
 
     if cmd.detached {
-        // Write the pid file with the selected version
+        // Write the pid file with the selected version, and track the hosts so `wash down` can stop all of them
         tokio::fs::write(install_dir.join(config::WASMCLOUD_PID_FILE), version).await?;
+        let mut tracked_hosts = read_started_hosts(&install_dir).await.unwrap_or_default();
+        tracked_hosts.extend(started_hosts.iter().cloned());
+        write_started_hosts(&install_dir, &tracked_hosts).await?;
+
         let url = format!("http://localhost:{}", host_port);
         out_json.insert("wasmcloud_url".to_string(), json!(url));
-        out_json.insert("wasmcloud_log".to_string(), json!(wasmcloud_log_path));
+        out_json.insert(
+            "wasmcloud_log".to_string(),
+            json!(started_hosts[0].log_file),
+        );
+        out_json.insert("hosts".to_string(), json!(started_hosts));
         out_json.insert("kill_cmd".to_string(), json!("wash down"));
         out_json.insert("nats_url".to_string(), json!(nats_listen_address));
 
@@ -542,17 +650,54 @@ pub(crate) async fn handle_up(cmd: UpCommand, output_kind: OutputKind) -> Result
             "\n🕸  NATS is running in the background at http://{nats_listen_address}"
         );
 
-        let _ = write!(
-            out_text,
-            "\n🌐 The wasmCloud dashboard is running at {}\n📜 Logs for the host are being written to {}",
-            url, wasmcloud_log_path.to_string_lossy()
-        );
+        if let [host] = &started_hosts[..] {
+            let _ = write!(
+                out_text,
+                "\n🌐 The wasmCloud dashboard is running at {}\n📜 Logs for the host are being written to {}",
+                url, host.log_file.to_string_lossy()
+            );
+        } else {
+            let _ = write!(
+                out_text,
+                "\n🌐 {} wasmCloud hosts are running in lattice {}:",
+                started_hosts.len(),
+                host_env[WASMCLOUD_LATTICE_PREFIX]
+            );
+            for host in &started_hosts {
+                let _ = write!(
+                    out_text,
+                    "\n   {} with its dashboard at http://localhost:{} and logs in {}",
+                    host.host_id,
+                    host.dashboard_port,
+                    host.log_file.to_string_lossy()
+                );
+            }
+        }
         let _ = write!(out_text, "\n\n⬇️  To stop wasmCloud, run \"wash down\"");
     }
 
     Ok(CommandOutput::new(out_text, out_json))
 }
 
+/// Stops the hosts, wadm and NATS server started by `wash up` when it fails to start wasmCloud
+async fn clean_up_failed_start(
+    host_children: Vec<Child>,
+    wadm_process: Option<Child>,
+    stop_nats_server: bool,
+    install_dir: &Path,
+) -> Result<()> {
+    for mut child in host_children {
+        let _ = child.kill().await;
+    }
+    if let Some(child) = wadm_process {
+        stop_wadm(child, install_dir).await?;
+    }
+    if stop_nats_server {
+        stop_nats(install_dir).await?;
+    }
+    Ok(())
+}
+
 /// Helper function to start the NATS binary, redirecting output to nats.log
 async fn start_nats(install_dir: &Path, nats_binary: &Path, nats_opts: NatsOpts) -> Result<Child> {
     // Ensure that leaf node remote connection can be established before launching NATS
@@ -596,8 +741,8 @@ async fn start_nats(install_dir: &Path, nats_binary: &Path, nats_opts: NatsOpts)
 
 /// Helper function to run wasmCloud in interactive mode
 async fn run_wasmcloud_interactive(
-    mut wasmcloud_child: Child,
-    port: u16,
+    mut wasmcloud_children: Vec<Child>,
+    hosts: &[StartedHost],
     output_kind: OutputKind,
 ) -> Result<()> {
     use std::sync::mpsc::channel;
@@ -619,12 +764,26 @@ async fn run_wasmcloud_interactive(
     });
 
     if output_kind != OutputKind::Json {
-        println!("🏃 Running in interactive mode, your host is running at http://localhost:{port}",);
+        if let [host] = hosts {
+            println!(
+                "🏃 Running in interactive mode, your host is running at http://localhost:{}",
+                host.dashboard_port
+            );
+        } else {
+            println!("🏃 Running in interactive mode, your hosts are running at:");
+            for host in hosts {
+                println!(
+                    "   http://localhost:{} (logs in {})",
+                    host.dashboard_port,
+                    host.log_file.display()
+                );
+            }
+        }
         println!("🚪 Press `CTRL+c` at any time to exit");
     }
 
-    // Create a separate thread to log host output
-    let handle = wasmcloud_child.stderr.take().map(|stderr| {
+    // Create a separate thread to log host output, when a single host logs to the terminal
+    let handle = wasmcloud_children[0].stderr.take().map(|stderr| {
         tokio::spawn(async {
             let mut lines = BufReader::new(stderr).lines();
             while let Ok(Some(line)) = lines.next_line().await {
@@ -642,7 +801,9 @@ async fn run_wasmcloud_interactive(
         handle.abort()
     };
 
-    wasmcloud_child.kill().await?;
+    for mut child in wasmcloud_children {
+        child.kill().await?;
+    }
     Ok(())
 }
 
@@ -710,6 +871,24 @@ async fn ensure_open_port(supplied_port: Option<u16>) -> Result<u16> {
     }
 }
 
+/// Finds an open port for the dashboard of each of `count` hosts, starting from the supplied port when there is one
+async fn ensure_open_ports(supplied_port: Option<u16>, count: u16) -> Result<Vec<u16>> {
+    let mut ports = vec![ensure_open_port(supplied_port).await?];
+    let mut port = ports[0];
+    while ports.len() < count.into() {
+        port = port
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Failed to find open ports for hosts"))?;
+        if tokio::net::TcpStream::connect((LOCALHOST, port))
+            .await
+            .is_err()
+        {
+            ports.push(port);
+        }
+    }
+    Ok(ports)
+}
+
 /// Helper function to create a NATS client from the same arguments wasmCloud will use
 async fn nats_client_from_wasmcloud_opts(wasmcloud_opts: &WasmcloudOpts) -> Result<Client> {
     nats_client_from_opts(
@@ -767,6 +946,12 @@ mod tests {
             "--enable-structured-logging",
             "--host-seed",
             "SNAP4UVNHVWSBJ5MHAQ6M3RB23S3ALA3O3A4RF25G2FQB5CCZJBBBWCKBY",
+            "--hosts",
+            "3",
+            "--label",
+            "team=platform",
+            "--label",
+            "2:zone=west",
             "--detached",
             "--nats-credsfile",
             TESTDIR,
@@ -842,6 +1027,11 @@ mod tests {
             Some("eyyjWT".to_string())
         );
         assert_eq!(up_all_flags.wasmcloud_opts.ctl_port, Some(4232));
+        assert_eq!(up_all_flags.hosts, 3);
+        assert_eq!(
+            up_all_flags.wasmcloud_opts.labels,
+            Some(vec!["team=platform".to_string(), "2:zone=west".to_string()])
+        );
         assert_eq!(
             up_all_flags.wasmcloud_opts.ctl_seed,
             Some("SUALIKDKMIUAKRT5536EXKC3CX73TJD3CFXZMJSHIKSP3LTYIIUQGCUVGA".to_string())
@@ -244,3 +244,94 @@ async fn integration_up_doesnt_kill_unowned_nats_serial() -> Result<()> {
     remove_dir_all(dir).unwrap();
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_up_can_start_and_stop_multiple_hosts_serial() -> Result<()> {
+    let dir = test_dir_with_subfolder("can_start_multiple_hosts");
+    let path = dir.join("washup.log");
+    let stdout = std::fs::File::create(&path).expect("could not create log file for wash up test");
+    let nats_port: u16 = 5896;
+
+    wait_for_no_hosts()
+        .await
+        .context("unexpected wasmcloud instance(s) running")?;
+
+    let status = Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args([
+            "up",
+            "--nats-port",
+            nats_port.to_string().as_ref(),
+            "-o",
+            "json",
+            "--detached",
+            "--hosts",
+            "2",
+            "--label",
+            "1:zone=east",
+            "--label",
+            "2:zone=west",
+        ])
+        .kill_on_drop(true)
+        .stdout(stdout)
+        .status()
+        .await
+        .context("up command failed to complete")?;
+
+    assert!(status.success());
+    let out = read_to_string(&path).expect("could not read output of wash up");
+    let out: serde_json::Value =
+        serde_json::from_str(&out).context("Unable to parse wash up output")?;
+    let started_hosts = out["hosts"]
+        .as_array()
+        .context("wash up output should list the started hosts")?;
+    assert_eq!(started_hosts.len(), 2);
+    assert_ne!(started_hosts[0]["host_id"], started_hosts[1]["host_id"]);
+    assert_ne!(started_hosts[0]["log_file"], started_hosts[1]["log_file"]);
+
+    // Wait for both hosts to show up in the lattice, with their own labels
+    let mut zones = Vec::new();
+    for _ in 0..10 {
+        let output = Command::new(env!("CARGO_BIN_EXE_wash"))
+            .args([
+                "get",
+                "hosts",
+                "--ctl-port",
+                nats_port.to_string().as_ref(),
+                "-o",
+                "json",
+            ])
+            .output()
+            .await
+            .context("Could not spawn wash get hosts process")?;
+        let hosts: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .context("Unable to parse wash get hosts output")?;
+        zones = hosts["hosts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|host| host["labels"]["zone"].as_str().map(String::from))
+            .collect::<Vec<_>>();
+        if zones.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    zones.sort();
+    assert_eq!(zones, vec!["east", "west"]);
+
+    // Stop both hosts without having to pass --all
+    Command::new(env!("CARGO_BIN_EXE_wash"))
+        .args(["down", "--ctl-port", nats_port.to_string().as_ref()])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("Could not spawn wash down process")?;
+
+    wait_for_no_hosts()
+        .await
+        .context("wasmcloud instances failed to exit cleanly (processes still left over)")?;
+
+    remove_dir_all(dir).unwrap();
+    Ok(())
+}